@@ -1,9 +1,10 @@
 use futures::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
 use std::time::Duration;
 use tower::ServiceBuilder;
-use tower_api_client::{pagination::PaginatedRequest, Client, Request, RequestData, ServiceExt};
+use tower_api_client::{
+    pagination::PaginatedRequest, Client, Path, Request, RequestData, ServiceExt,
+};
 
 #[derive(Clone, Deserialize, Debug)]
 struct Return {}
@@ -18,7 +19,7 @@ impl Request for GetPassengers {
     type Data = Self;
     type Response = Return;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/v1/passenger".into()
     }
 
@@ -40,14 +41,15 @@ impl PaginatedRequest for GetPassengers {
     }
 
     fn update_request(&mut self, page: &usize) {
-        self.page = Some(*page as usize)
+        self.page = Some(*page)
     }
 }
 
 #[tokio::main]
 pub async fn main() {
     env_logger::init();
-    // A client that will send one paginated request per second, and error after 4 requests
+    // A client that will send one paginated request per second, time out requests after 10
+    // seconds, and error after 4 requests
     let client = ServiceBuilder::new()
         .filter(|req: GetPassengers| {
             if req.page.unwrap_or(0) < 4 {
@@ -57,6 +59,7 @@ pub async fn main() {
             }
         })
         .rate_limit(1, Duration::from_secs(1))
+        .timeout(Duration::from_secs(10))
         .service(Client::new("https://api.instantwebtools.net"));
 
     let req = GetPassengers {
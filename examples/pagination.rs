@@ -1,9 +1,7 @@
-use futures::{StreamExt, TryStreamExt as _};
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
-use stream_flatten_iters::TryStreamExt;
-use tower_api_client::pagination::PaginatedRequest;
-use tower_api_client::{Client, Request, RequestData, ServiceExt as _};
+use tower_api_client::pagination::PageNumberPagination;
+use tower_api_client::{Client, Path, Request, RequestData, ServiceExt as _};
 
 #[derive(Clone, Debug, Serialize)]
 struct GetPassengers {
@@ -27,7 +25,7 @@ impl Request for GetPassengers {
     type Data = Self;
     type Response = PassengersWrapper;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/v1/passenger".into()
     }
 
@@ -36,45 +34,24 @@ impl Request for GetPassengers {
     }
 }
 
-impl PaginatedRequest for GetPassengers {
-    type PaginationData = usize;
-    fn get_page(&self) -> Option<usize> {
-        self.page
-    }
-
-    fn next_page(&self, prev_page: Option<&usize>, response: &PassengersWrapper) -> Option<usize> {
-        match prev_page {
-            None => Some(1),
-            Some(prev_page) => {
-                if prev_page == &response.total_pages {
-                    None
-                } else {
-                    Some(prev_page + 1)
-                }
-            }
-        }
-    }
-
-    fn update_request(&mut self, page: &usize) {
-        self.page = Some(*page as usize)
-    }
-}
-
 #[tokio::main]
 pub async fn main() {
     env_logger::init();
     let client = Client::new("https://api.instantwebtools.net");
 
-    let req = GetPassengers {
-        page: Some(3),
-        size: 10,
-    };
+    let req = PageNumberPagination::new(
+        GetPassengers {
+            page: None,
+            size: 10,
+        },
+        |req, page| req.page = Some(page),
+    )
+    .first_page(3)
+    .last_page(|res: &PassengersWrapper| res.total_pages);
 
     client
-        .paginate(req)
-        .take(5)
-        .map(|maybe_wrapper| maybe_wrapper.map(|wrapper| wrapper.data))
-        .try_flatten_iters()
+        .paginate_items(req, |wrapper| wrapper.data)
+        .max_items(50)
         .try_for_each(|res| async move {
             println!("{}", res.name.unwrap_or_else(|| String::from("No name")));
             Ok(())
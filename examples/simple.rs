@@ -1,7 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
 use tower::ServiceExt;
-use tower_api_client::{Client, Request, RequestData};
+use tower_api_client::{Client, Path, Request, RequestData};
 
 #[derive(Serialize)]
 struct GetPassengers {
@@ -23,7 +22,7 @@ impl Request for GetPassengers {
     type Data = Self;
     type Response = PassengersWrapper;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/v1/passenger".into()
     }
 
@@ -0,0 +1,222 @@
+//! Authentication of outgoing requests.
+//!
+//! An [`Authenticator`] is given every request after it has been fully built, and can add
+//! headers, query parameters or signatures to it. The schemes configured through the
+//! [`ClientBuilder`](crate::ClientBuilder) are implemented on top of this trait, and custom
+//! schemes can be configured with [`ClientBuilder::authenticator`](crate::ClientBuilder::authenticator).
+use crate::endpoint;
+use crate::error::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::{self, BoxFuture};
+use hyper::body::Bytes;
+use hyper::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use hyper::Uri;
+use secrecy::{ExposeSecret, Secret};
+use std::time::SystemTime;
+use url::Url;
+
+mod signing;
+#[cfg(feature = "sigv4")]
+mod sigv4;
+
+pub use signing::{HmacAuth, SignatureEncoding, SigningInput, TimestampFormat};
+#[cfg(feature = "sigv4")]
+pub use sigv4::{AwsCredentials, CredentialsProvider, SigV4Auth};
+
+/// Authenticates requests before they are sent.
+///
+/// The request passed to the authenticator contains the method, URI and headers. The body is only
+/// included for authenticators which sign it, as reported by [`Authenticator::needs_body`], since
+/// it has to be buffered in memory for that, including streaming multipart bodies. Otherwise, the
+/// body passed to the authenticator is empty, and the body of the request is sent as it is.
+pub trait Authenticator: Send + Sync + 'static {
+    /// Authenticate the request, modifying it in place.
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// Called when a request authenticated by `authenticate` is rejected with
+    /// `401 Unauthorized`. If this returns `true`, the request is authenticated and sent again
+    /// once, which allows for discarding expired credentials. Defaults to `false`.
+    fn unauthorized<'a>(&'a self, _request: &'a hyper::Request<Bytes>) -> BoxFuture<'a, bool> {
+        Box::pin(future::ready(false))
+    }
+
+    /// Whether the authenticator needs the body of requests, such as to sign it. Defaults to
+    /// `false`.
+    fn needs_body(&self) -> bool {
+        false
+    }
+}
+
+/// A source of the current time, used by authenticators that sign timestamps. Closures returning a
+/// `SystemTime` can be used as clocks, which is useful for deterministic tests.
+pub trait Clock: Send + Sync + 'static {
+    /// The current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync + 'static,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+/// Sends a token in the `Authorization` header, using the `Bearer` scheme.
+pub struct BearerAuth {
+    token: Secret<String>,
+}
+
+impl BearerAuth {
+    pub fn new<S: ToString>(token: S) -> Self {
+        Self {
+            token: Secret::new(token.to_string()),
+        }
+    }
+}
+
+impl Authenticator for BearerAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(set_bearer(request, &self.token)))
+    }
+}
+
+/// Sends a username and an optional password in the `Authorization` header, using the `Basic`
+/// scheme.
+pub struct BasicAuth {
+    user: String,
+    pass: Option<Secret<String>>,
+}
+
+impl BasicAuth {
+    pub fn new<T: Into<Option<S>>, S: ToString>(user: S, pass: T) -> Self {
+        Self {
+            user: user.to_string(),
+            pass: pass.into().map(|x| Secret::new(x.to_string())),
+        }
+    }
+}
+
+impl Authenticator for BasicAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        let creds = format!(
+            "{}:{}",
+            self.user,
+            self.pass
+                .as_ref()
+                .map(|x| x.expose_secret().as_str())
+                .unwrap_or("")
+        );
+        let encoded = STANDARD.encode(creds);
+        let res = HeaderValue::from_str(&format!("Basic {}", encoded))
+            .map(|mut header_value| {
+                header_value.set_sensitive(true);
+                request.headers_mut().insert(AUTHORIZATION, header_value);
+            })
+            .map_err(|e| hyper::http::Error::from(e).into());
+        Box::pin(future::ready(res))
+    }
+}
+
+/// Sends credentials as query parameters.
+pub struct QueryAuth {
+    pairs: Vec<(String, Secret<String>)>,
+}
+
+impl QueryAuth {
+    pub fn new<S: ToString>(pairs: Vec<(S, S)>) -> Self {
+        Self {
+            pairs: pairs
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), Secret::new(v.to_string())))
+                .collect(),
+        }
+    }
+
+    fn append(&self, request: &mut hyper::Request<Bytes>) -> Result<()> {
+        let pairs: Vec<_> = self
+            .pairs
+            .iter()
+            .map(|(k, v)| (k, v.expose_secret()))
+            .collect();
+        let query = serde_urlencoded::to_string(pairs)?;
+        append_query(request, &query)
+    }
+}
+
+impl Authenticator for QueryAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.append(request)))
+    }
+}
+
+/// Sends credentials in custom headers.
+pub struct HeaderAuth {
+    headers: HeaderMap<HeaderValue>,
+}
+
+impl HeaderAuth {
+    /// Create a `HeaderAuth` sending the given headers, which are marked as sensitive.
+    pub fn new(headers: HeaderMap<HeaderValue>) -> Self {
+        let mut headers = headers;
+        for value in headers.values_mut() {
+            value.set_sensitive(true);
+        }
+        Self { headers }
+    }
+}
+
+impl Authenticator for HeaderAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        for (k, v) in &self.headers {
+            request.headers_mut().insert(k, v.clone());
+        }
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+pub(crate) fn set_bearer(
+    request: &mut hyper::Request<Bytes>,
+    token: &Secret<String>,
+) -> Result<()> {
+    let mut header_value = HeaderValue::from_str(&format!("Bearer {}", token.expose_secret()))
+        .map_err(hyper::http::Error::from)?;
+    header_value.set_sensitive(true);
+    request.headers_mut().insert(AUTHORIZATION, header_value);
+    Ok(())
+}
+
+pub(crate) fn append_query(request: &mut hyper::Request<Bytes>, query: &str) -> Result<()> {
+    let mut url = Url::parse(&request.uri().to_string())?;
+    endpoint::append_query(&mut url, query);
+    *request.uri_mut() = url
+        .as_str()
+        .parse::<Uri>()
+        .map_err(hyper::http::Error::from)?;
+    Ok(())
+}
@@ -0,0 +1,375 @@
+use super::{append_query, Authenticator, Clock, SystemClock};
+use crate::error::{Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::{self, BoxFuture};
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::Method;
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+use std::time::UNIX_EPOCH;
+
+/// How the timestamp sent with a signed request is formatted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Seconds since the Unix epoch.
+    Seconds,
+    /// Milliseconds since the Unix epoch.
+    #[default]
+    Millis,
+    /// An HTTP date, as used in the `Date` header.
+    HttpDate,
+}
+
+/// How the signature is encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignatureEncoding {
+    /// Lowercase hex.
+    #[default]
+    Hex,
+    /// Standard base64 with padding.
+    Base64,
+}
+
+enum Placement {
+    Header(HeaderName),
+    Query(String),
+}
+
+enum Segment {
+    Literal(String),
+    Method,
+    Path,
+    Query,
+    PathAndQuery,
+    Timestamp,
+    Body,
+    Header(HeaderName),
+}
+
+type Canonicalize = Box<dyn Fn(&SigningInput<'_>) -> Vec<u8> + Send + Sync>;
+
+/// The parts of a request available when building the string to sign.
+pub struct SigningInput<'a> {
+    request: &'a hyper::Request<Bytes>,
+    timestamp: &'a str,
+}
+
+impl<'a> SigningInput<'a> {
+    /// The request being signed. If the timestamp is sent with the request, it has already been
+    /// added.
+    pub fn request(&self) -> &'a hyper::Request<Bytes> {
+        self.request
+    }
+
+    /// The HTTP method of the request.
+    pub fn method(&self) -> &'a Method {
+        self.request.method()
+    }
+
+    /// The path of the request, as sent to the server.
+    pub fn path(&self) -> &'a str {
+        self.request.uri().path()
+    }
+
+    /// The query string, without the leading `?`. Empty if the request has no query.
+    pub fn query(&self) -> &'a str {
+        self.request.uri().query().unwrap_or("")
+    }
+
+    /// The path, followed by the query string if the request has one.
+    pub fn path_and_query(&self) -> &'a str {
+        self.request
+            .uri()
+            .path_and_query()
+            .map(|x| x.as_str())
+            .unwrap_or("/")
+    }
+
+    /// The formatted timestamp covered by the signature.
+    pub fn timestamp(&self) -> &'a str {
+        self.timestamp
+    }
+
+    /// The body of the request.
+    pub fn body(&self) -> &'a [u8] {
+        self.request.body()
+    }
+
+    /// The value of a header of the request, if it is set.
+    pub fn header(&self, name: &str) -> Option<&'a HeaderValue> {
+        self.request.headers().get(name)
+    }
+}
+
+/// Signs requests with HMAC-SHA256, as required by many exchange and webhook-style APIs.
+///
+/// The string to sign is built from the request according to a template, and the signature is
+/// sent in a header or query parameter along with the timestamp it covers. By default, the
+/// template is `{timestamp}{method}{path_and_query}{body}`, the timestamp is sent in milliseconds
+/// in the `X-Timestamp` header and the hex-encoded signature in the `X-Signature` header.
+///
+/// ```
+/// use tower_api_client::auth::HmacAuth;
+///
+/// // Sign the query string, which includes the timestamp, and send the signature as a query
+/// // parameter
+/// let auth = HmacAuth::new("secret")
+///     .template("{query}")
+///     .timestamp_query("timestamp")
+///     .signature_query("signature");
+/// ```
+pub struct HmacAuth {
+    key: Secret<Vec<u8>>,
+    canonicalize: Canonicalize,
+    needs_body: bool,
+    clock: Box<dyn Clock>,
+    timestamp_format: TimestampFormat,
+    timestamp: Option<Placement>,
+    signature: Placement,
+    encoding: SignatureEncoding,
+    error: Option<String>,
+}
+
+impl HmacAuth {
+    /// Create an `HmacAuth` signing requests with the given secret key.
+    pub fn new<K: AsRef<[u8]>>(key: K) -> Self {
+        Self {
+            key: Secret::new(key.as_ref().to_vec()),
+            canonicalize: Box::new(|_| Vec::new()),
+            needs_body: false,
+            clock: Box::new(SystemClock),
+            timestamp_format: TimestampFormat::default(),
+            timestamp: Some(Placement::Header(HeaderName::from_static("x-timestamp"))),
+            signature: Placement::Header(HeaderName::from_static("x-signature")),
+            encoding: SignatureEncoding::default(),
+            error: None,
+        }
+        .template("{timestamp}{method}{path_and_query}{body}")
+    }
+
+    /// Set the template for the string to sign. Placeholders are written in braces, and can be
+    /// any of `{method}`, `{path}`, `{query}`, `{path_and_query}`, `{timestamp}`, `{body}` or
+    /// `{header:<name>}`. Everything else is copied as is.
+    ///
+    /// An invalid template is reported as an [`Error::Authentication`] when signing requests.
+    pub fn template(mut self, template: &str) -> Self {
+        match parse_template(template) {
+            Ok(segments) => {
+                self.needs_body = segments
+                    .iter()
+                    .any(|segment| matches!(segment, Segment::Body));
+                self.canonicalize = Box::new(move |input| render(&segments, input));
+            }
+            Err(msg) => self.fail(msg),
+        }
+        self
+    }
+
+    /// Build the string to sign with a function, for schemes that can't be expressed as a
+    /// template. The body of requests is always buffered for the function, while templates only
+    /// buffer it if they contain `{body}`.
+    pub fn canonicalize<F, T>(mut self, canonicalize: F) -> Self
+    where
+        F: Fn(&SigningInput<'_>) -> T + Send + Sync + 'static,
+        T: AsRef<[u8]>,
+    {
+        self.canonicalize = Box::new(move |input| canonicalize(input).as_ref().to_vec());
+        self.needs_body = true;
+        self
+    }
+
+    /// Set the clock used for timestamps. Defaults to the system clock.
+    pub fn clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Set how the timestamp is formatted. Defaults to milliseconds since the Unix epoch.
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// Send the timestamp in the given header.
+    pub fn timestamp_header<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.timestamp = self.header_placement(name.as_ref());
+        self
+    }
+
+    /// Send the timestamp in the given query parameter.
+    pub fn timestamp_query<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.timestamp = Some(Placement::Query(name.as_ref().to_string()));
+        self
+    }
+
+    /// Don't send the timestamp with the request, only include it in the string to sign.
+    pub fn omit_timestamp(mut self) -> Self {
+        self.timestamp = None;
+        self
+    }
+
+    /// Send the signature in the given header.
+    pub fn signature_header<S: AsRef<str>>(mut self, name: S) -> Self {
+        if let Some(placement) = self.header_placement(name.as_ref()) {
+            self.signature = placement;
+        }
+        self
+    }
+
+    /// Send the signature in the given query parameter.
+    pub fn signature_query<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.signature = Placement::Query(name.as_ref().to_string());
+        self
+    }
+
+    /// Set how the signature is encoded. Defaults to hex.
+    pub fn encoding(mut self, encoding: SignatureEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    fn header_placement(&mut self, name: &str) -> Option<Placement> {
+        match HeaderName::from_bytes(name.as_bytes()) {
+            Ok(name) => Some(Placement::Header(name)),
+            Err(_) => {
+                self.fail(format!("Invalid header name {:?}", name));
+                None
+            }
+        }
+    }
+
+    fn fail(&mut self, msg: String) {
+        if self.error.is_none() {
+            self.error = Some(msg);
+        }
+    }
+
+    fn timestamp(&self) -> Result<String> {
+        let now = self.clock.now();
+        let since_epoch = || {
+            now.duration_since(UNIX_EPOCH)
+                .map_err(|_| Error::Authentication {
+                    msg: "Clock is set before the Unix epoch".into(),
+                })
+        };
+        Ok(match self.timestamp_format {
+            TimestampFormat::Seconds => since_epoch()?.as_secs().to_string(),
+            TimestampFormat::Millis => since_epoch()?.as_millis().to_string(),
+            TimestampFormat::HttpDate => httpdate::fmt_http_date(now),
+        })
+    }
+
+    fn sign(&self, request: &mut hyper::Request<Bytes>) -> Result<()> {
+        if let Some(msg) = &self.error {
+            return Err(Error::Authentication { msg: msg.clone() });
+        }
+        let timestamp = self.timestamp()?;
+        if let Some(placement) = &self.timestamp {
+            place(request, placement, &timestamp)?;
+        }
+
+        let canonical = (self.canonicalize)(&SigningInput {
+            request,
+            timestamp: &timestamp,
+        });
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.expose_secret())
+            .expect("HMAC can take a key of any size");
+        mac.update(&canonical);
+        let signature = mac.finalize().into_bytes();
+        let signature = match self.encoding {
+            SignatureEncoding::Hex => hex::encode(signature),
+            SignatureEncoding::Base64 => STANDARD.encode(signature),
+        };
+        place(request, &self.signature, &signature)
+    }
+}
+
+impl Authenticator for HmacAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.sign(request)))
+    }
+
+    fn needs_body(&self) -> bool {
+        self.needs_body
+    }
+}
+
+fn place(request: &mut hyper::Request<Bytes>, placement: &Placement, value: &str) -> Result<()> {
+    match placement {
+        Placement::Header(name) => {
+            let value = HeaderValue::from_str(value).map_err(hyper::http::Error::from)?;
+            request.headers_mut().insert(name, value);
+            Ok(())
+        }
+        Placement::Query(name) => {
+            let query = serde_urlencoded::to_string([(name, value)])?;
+            append_query(request, &query)
+        }
+    }
+}
+
+fn parse_template(template: &str) -> std::result::Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => return Err(format!("Unclosed placeholder in template {:?}", template)),
+        };
+        let segment = match &rest[start + 1..end] {
+            "method" => Segment::Method,
+            "path" => Segment::Path,
+            "query" => Segment::Query,
+            "path_and_query" => Segment::PathAndQuery,
+            "timestamp" => Segment::Timestamp,
+            "body" => Segment::Body,
+            placeholder => match placeholder.strip_prefix("header:") {
+                Some(name) => Segment::Header(
+                    HeaderName::from_bytes(name.as_bytes())
+                        .map_err(|_| format!("Invalid header name {:?} in template", name))?,
+                ),
+                None => {
+                    return Err(format!(
+                        "Unknown placeholder {{{}}} in template {:?}",
+                        placeholder, template
+                    ))
+                }
+            },
+        };
+        segments.push(segment);
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+fn render(segments: &[Segment], input: &SigningInput<'_>) -> Vec<u8> {
+    let mut canonical = Vec::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(literal) => canonical.extend_from_slice(literal.as_bytes()),
+            Segment::Method => canonical.extend_from_slice(input.method().as_str().as_bytes()),
+            Segment::Path => canonical.extend_from_slice(input.path().as_bytes()),
+            Segment::Query => canonical.extend_from_slice(input.query().as_bytes()),
+            Segment::PathAndQuery => canonical.extend_from_slice(input.path_and_query().as_bytes()),
+            Segment::Timestamp => canonical.extend_from_slice(input.timestamp().as_bytes()),
+            Segment::Body => canonical.extend_from_slice(input.body()),
+            Segment::Header(name) => {
+                if let Some(value) = input.request().headers().get(name) {
+                    canonical.extend_from_slice(value.as_bytes());
+                }
+            }
+        }
+    }
+    canonical
+}
@@ -0,0 +1,332 @@
+use super::{Authenticator, Clock, SystemClock};
+use crate::error::{Error, Result};
+use futures::future::{self, BoxFuture};
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::{HeaderValue, AUTHORIZATION, HOST};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use secrecy::{ExposeSecret, Secret};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Characters which are percent-encoded in canonical URIs and query strings, which is everything
+/// but the unreserved characters of RFC 3986.
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Headers which are not signed, as they may be changed by proxies or set after signing.
+const UNSIGNED_HEADERS: [&str; 4] = ["authorization", "expect", "user-agent", "x-amzn-trace-id"];
+
+/// AWS credentials used for signing requests.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: Secret<String>,
+    session_token: Option<Secret<String>>,
+}
+
+impl AwsCredentials {
+    pub fn new<I: ToString, S: ToString>(access_key_id: I, secret_access_key: S) -> Self {
+        Self {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: Secret::new(secret_access_key.to_string()),
+            session_token: None,
+        }
+    }
+
+    /// Set the session token of temporary credentials, which is sent in the
+    /// `X-Amz-Security-Token` header.
+    pub fn session_token<S: ToString>(mut self, session_token: S) -> Self {
+        self.session_token = Some(Secret::new(session_token.to_string()));
+        self
+    }
+}
+
+/// Provides the credentials for signing each request.
+///
+/// `AwsCredentials` provides itself. Temporary credentials, e.g. from STS or an instance metadata
+/// service, can be refreshed by implementing this trait. It is called for every request, so
+/// implementors should cache credentials until they expire.
+pub trait CredentialsProvider: Send + Sync + 'static {
+    fn credentials(&self) -> BoxFuture<'_, Result<AwsCredentials>>;
+}
+
+impl CredentialsProvider for AwsCredentials {
+    fn credentials(&self) -> BoxFuture<'_, Result<AwsCredentials>> {
+        Box::pin(future::ready(Ok(self.clone())))
+    }
+}
+
+/// Signs requests with [AWS Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_aws-signing.html).
+///
+/// All headers of the request are signed, except for `User-Agent` and a few others which are
+/// commonly modified in transit. If the request has no `Host` header, one is added based on its
+/// URI.
+///
+/// ```
+/// use tower_api_client::auth::{AwsCredentials, SigV4Auth};
+///
+/// let credentials = AwsCredentials::new("access-key-id", "secret-access-key");
+/// let auth = SigV4Auth::new("us-east-1", "execute-api", credentials);
+/// ```
+pub struct SigV4Auth {
+    region: String,
+    service: String,
+    credentials: Box<dyn CredentialsProvider>,
+    clock: Box<dyn Clock>,
+    unsigned_payload: bool,
+    content_sha256_header: bool,
+}
+
+impl SigV4Auth {
+    pub fn new<R, S, C>(region: R, service: S, credentials: C) -> Self
+    where
+        R: ToString,
+        S: ToString,
+        C: CredentialsProvider,
+    {
+        Self {
+            region: region.to_string(),
+            service: service.to_string(),
+            credentials: Box::new(credentials),
+            clock: Box::new(SystemClock),
+            unsigned_payload: false,
+            content_sha256_header: false,
+        }
+    }
+
+    /// Set the clock used for the signing date. Defaults to the system clock.
+    pub fn clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Don't sign the body of requests, using `UNSIGNED-PAYLOAD` as the payload hash instead. This
+    /// also sends the `X-Amz-Content-Sha256` header, as required by S3. Streamed bodies are then
+    /// sent without being buffered in memory.
+    pub fn unsigned_payload(mut self, unsigned_payload: bool) -> Self {
+        self.unsigned_payload = unsigned_payload;
+        self
+    }
+
+    /// Send the payload hash in the `X-Amz-Content-Sha256` header, as required by S3.
+    pub fn content_sha256_header(mut self, content_sha256_header: bool) -> Self {
+        self.content_sha256_header = content_sha256_header;
+        self
+    }
+
+    fn sign(&self, request: &mut hyper::Request<Bytes>, credentials: AwsCredentials) -> Result<()> {
+        let (date, time) = format_date(self.clock.now())?;
+        let timestamp = format!("{}T{}Z", date, time);
+        let payload_hash = if self.unsigned_payload {
+            UNSIGNED_PAYLOAD.to_string()
+        } else {
+            hex::encode(Sha256::digest(request.body()))
+        };
+
+        if !request.headers().contains_key(HOST) {
+            let host = host(request.uri())?;
+            request.headers_mut().insert(HOST, host);
+        }
+        let headers = request.headers_mut();
+        headers.insert("x-amz-date", header_value(&timestamp)?);
+        if let Some(session_token) = &credentials.session_token {
+            let mut value = header_value(session_token.expose_secret())?;
+            value.set_sensitive(true);
+            headers.insert("x-amz-security-token", value);
+        }
+        if self.unsigned_payload || self.content_sha256_header {
+            headers.insert("x-amz-content-sha256", header_value(&payload_hash)?);
+        }
+
+        let (canonical_headers, signed_headers) = canonical_headers(request)?;
+        let canonical_request = [
+            request.method().as_str(),
+            &canonical_uri(request.uri().path(), self.service == "s3"),
+            &canonical_query(request.uri().query().unwrap_or("")),
+            &canonical_headers,
+            &signed_headers,
+            &payload_hash,
+        ]
+        .join("\n");
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = [
+            ALGORITHM,
+            &timestamp,
+            &scope,
+            &hex::encode(Sha256::digest(canonical_request.as_bytes())),
+        ]
+        .join("\n");
+
+        let secret = format!("AWS4{}", credentials.secret_access_key.expose_secret());
+        let key = hmac(secret.as_bytes(), date.as_bytes());
+        let key = hmac(&key, self.region.as_bytes());
+        let key = hmac(&key, self.service.as_bytes());
+        let key = hmac(&key, b"aws4_request");
+        let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM, credentials.access_key_id, scope, signed_headers, signature
+        );
+        let mut authorization = header_value(&authorization)?;
+        authorization.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, authorization);
+        Ok(())
+    }
+}
+
+impl Authenticator for SigV4Auth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let credentials = self.credentials.credentials().await?;
+            self.sign(request, credentials)
+        })
+    }
+
+    fn needs_body(&self) -> bool {
+        !self.unsigned_payload
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value).map_err(|e| hyper::http::Error::from(e).into())
+}
+
+/// The `Host` header hyper would send for the URI, which omits default ports.
+fn host(uri: &hyper::Uri) -> Result<HeaderValue> {
+    let host = uri.host().ok_or_else(|| Error::Authentication {
+        msg: format!("Cannot sign request to {} without a host", uri),
+    })?;
+    let default_port = match uri.scheme_str() {
+        Some("https") => Some(443),
+        Some("http") => Some(80),
+        _ => None,
+    };
+    match uri.port_u16() {
+        Some(port) if Some(port) != default_port => header_value(&format!("{}:{}", host, port)),
+        _ => header_value(host),
+    }
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, ENCODE_SET).to_string()
+}
+
+fn decode(value: &str) -> String {
+    percent_decode_str(value).decode_utf8_lossy().into_owned()
+}
+
+fn canonical_uri(path: &str, s3: bool) -> String {
+    if path.is_empty() {
+        return "/".into();
+    }
+    // S3 signs the path as sent, while other services sign the normalized path
+    if s3 {
+        return path
+            .split('/')
+            .map(|segment| encode(&decode(segment)))
+            .collect::<Vec<_>>()
+            .join("/");
+    }
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/').skip(1) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut uri: String = segments
+        .iter()
+        .map(|segment| format!("/{}", encode(&decode(segment))))
+        .collect();
+    if uri.is_empty() || (path.ends_with('/') && !segments.is_empty()) {
+        uri.push('/');
+    }
+    uri
+}
+
+fn canonical_query(query: &str) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (encode(&decode(key)), encode(&decode(value)))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn canonical_headers(request: &hyper::Request<Bytes>) -> Result<(String, String)> {
+    let mut headers: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (name, value) in request.headers() {
+        if UNSIGNED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        let value = value.to_str().map_err(|_| Error::Authentication {
+            msg: format!("Cannot sign non-ASCII value of header {}", name),
+        })?;
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        headers.entry(name.as_str()).or_default().push(value);
+    }
+    let canonical = headers
+        .iter()
+        .map(|(name, values)| format!("{}:{}\n", name, values.join(",")))
+        .collect();
+    let signed = headers.keys().copied().collect::<Vec<_>>().join(";");
+    Ok((canonical, signed))
+}
+
+/// Format the time as the date (`YYYYMMDD`) and time (`HHMMSS`) in UTC.
+fn format_date(time: SystemTime) -> Result<(String, String)> {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::Authentication {
+            msg: "Clock is set before the Unix epoch".into(),
+        })?
+        .as_secs();
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Convert days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    Ok((
+        format!("{:04}{:02}{:02}", year, month, day),
+        format!("{:02}{:02}{:02}", secs / 3600, secs % 3600 / 60, secs % 60),
+    ))
+}
@@ -0,0 +1,320 @@
+use crate::auth::{Authenticator, BasicAuth, BearerAuth, HeaderAuth, HmacAuth, QueryAuth};
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::oauth2::OAuth2;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use hyper::Client as HyperClient;
+use hyper_tls::HttpsConnector;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// A builder for configuring a [`Client`].
+///
+/// Invalid input, such as a malformed base url or header, is reported as an error when the client
+/// is built, rather than when it is passed to the builder.
+pub struct ClientBuilder {
+    base_url: String,
+    hyper_client: Option<HyperClient<HttpsConnector<HttpConnector>>>,
+    default_headers: HeaderMap<HeaderValue>,
+    auth: Option<Arc<dyn Authenticator>>,
+    oauth2: Option<OAuth2>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    https_only: bool,
+    tcp_nodelay: bool,
+    tcp_keepalive: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+    error: Option<Error>,
+}
+
+impl ClientBuilder {
+    /// Create a new `ClientBuilder` for a client sending requests to `base_url`.
+    pub fn new<S: AsRef<str>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.as_ref().to_string(),
+            hyper_client: None,
+            default_headers: HeaderMap::new(),
+            auth: None,
+            oauth2: None,
+            connect_timeout: None,
+            timeout: None,
+            read_timeout: None,
+            https_only: false,
+            tcp_nodelay: false,
+            tcp_keepalive: None,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+            error: None,
+        }
+    }
+
+    /// Use an existing Hyper client to send requests. Note that the connector options of the
+    /// builder, including the connect timeout, are ignored in that case.
+    pub fn hyper_client(mut self, client: HyperClient<HttpsConnector<HttpConnector>>) -> Self {
+        self.hyper_client = Some(client);
+        self
+    }
+
+    /// Add a header sent with every request.
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<hyper::http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<hyper::http::Error>,
+    {
+        match header(key, value) {
+            Ok((key, value)) => {
+                self.default_headers.append(key, value);
+            }
+            Err(e) => self.fail(e),
+        }
+        self
+    }
+
+    /// Add headers sent with every request.
+    pub fn default_headers(mut self, default_headers: HeaderMap<HeaderValue>) -> Self {
+        // Additional values of a header are yielded without a name, so the previous one is kept
+        let mut name = None;
+        for (key, value) in default_headers {
+            name = key.or(name);
+            if let Some(name) = &name {
+                self.default_headers.append(name.clone(), value);
+            }
+        }
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request.
+    pub fn user_agent<V>(mut self, user_agent: V) -> Self
+    where
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<hyper::http::Error>,
+    {
+        match HeaderValue::try_from(user_agent) {
+            Ok(value) => {
+                self.default_headers.insert(USER_AGENT, value);
+            }
+            Err(e) => self.fail(e.into()),
+        }
+        self
+    }
+
+    /// Authenticate requests with a custom [`Authenticator`], replacing any previously configured
+    /// authentication.
+    pub fn authenticator<A: Authenticator>(mut self, authenticator: A) -> Self {
+        self.auth = Some(Arc::new(authenticator));
+        self.oauth2 = None;
+        self
+    }
+
+    /// Enable bearer authentication for the client.
+    pub fn bearer_auth<S: ToString>(mut self, token: S) -> Self {
+        let token = token.to_string();
+        if let Err(e) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            self.fail(e.into());
+        }
+        self.authenticator(BearerAuth::new(token))
+    }
+
+    /// Enable basic authentication for the client.
+    pub fn basic_auth<T: Into<Option<S>>, S: ToString>(self, user: S, pass: T) -> Self {
+        self.authenticator(BasicAuth::new(user, pass))
+    }
+
+    /// Enable query authentication for the client.
+    pub fn query_auth<S: ToString>(self, pairs: Vec<(S, S)>) -> Self {
+        self.authenticator(QueryAuth::new(pairs))
+    }
+
+    /// Enable custom header authentication for the client.
+    pub fn header_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            match header(k.to_string(), v.to_string()) {
+                Ok((key, value)) => {
+                    map.insert(key, value);
+                }
+                Err(e) => self.fail(e),
+            }
+        }
+        self.authenticator(HeaderAuth::new(map))
+    }
+
+    /// Enable OAuth2 authentication for the client, sending the access token as a bearer token.
+    /// A request rejected with `401 Unauthorized` is retried once with a freshly fetched token.
+    ///
+    /// Tokens are fetched with the Hyper client of the `Client`, unless another one is set with
+    /// [`OAuth2::hyper_client`].
+    pub fn oauth2(mut self, oauth2: OAuth2) -> Self {
+        self.auth = None;
+        self.oauth2 = Some(oauth2);
+        self
+    }
+
+    /// Enable HMAC request signing for the client.
+    pub fn hmac_auth(self, hmac: HmacAuth) -> Self {
+        self.authenticator(hmac)
+    }
+
+    /// Enable AWS Signature Version 4 signing for the client.
+    #[cfg(feature = "sigv4")]
+    pub fn sigv4_auth(self, sigv4: crate::auth::SigV4Auth) -> Self {
+        self.authenticator(sigv4)
+    }
+
+    /// Set the timeout for establishing a connection to the server.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for requests as a whole, which can be overridden by `Request::timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for reading the body of responses. Does not apply to `StreamingBody`
+    /// responses, which are read by the caller.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Only allow connections over HTTPS.
+    pub fn https_only(mut self, https_only: bool) -> Self {
+        self.https_only = https_only;
+        self
+    }
+
+    /// Set the `TCP_NODELAY` option on connections.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    /// Set the interval of TCP keepalive probes on connections.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Set how long idle connections are kept in the pool. Pass `None` to keep them indefinitely.
+    pub fn pool_idle_timeout<D: Into<Option<Duration>>>(mut self, timeout: D) -> Self {
+        self.pool_idle_timeout = timeout.into();
+        self
+    }
+
+    /// Set the maximum number of idle connections kept in the pool per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Build the `Client`, returning the first error encountered while configuring it.
+    pub fn build(mut self) -> Result<Client> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        let base_url = self.base_url()?;
+        Ok(self.finish(Ok(base_url)))
+    }
+
+    /// Build the `Client` without failing on an invalid base url, which is reported when sending
+    /// requests instead. Used by the constructors of `Client`, which don't return errors.
+    pub(crate) fn build_lenient(self) -> Client {
+        let base_url = self.base_url();
+        self.finish(base_url)
+    }
+
+    fn base_url(&self) -> std::result::Result<Url, InvalidBaseUrl> {
+        let mut url = Url::parse(&self.base_url).map_err(InvalidBaseUrl::Parse)?;
+        url.set_fragment(None);
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(InvalidBaseUrl::UnsupportedScheme(url.scheme().to_string()));
+        }
+        if self.https_only && url.scheme() != "https" {
+            return Err(InvalidBaseUrl::UnsupportedScheme(url.scheme().to_string()));
+        }
+        Ok(url)
+    }
+
+    /// Build the Hyper client from the connector options, unless one has been set.
+    fn connector(&self) -> HyperClient<HttpsConnector<HttpConnector>> {
+        if let Some(client) = &self.hyper_client {
+            return client.clone();
+        }
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(self.connect_timeout);
+        http.set_nodelay(self.tcp_nodelay);
+        http.set_keepalive(self.tcp_keepalive);
+        let mut https = HttpsConnector::new_with_connector(http);
+        https.https_only(self.https_only);
+        HyperClient::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .build(https)
+    }
+
+    fn finish(self, base_url: std::result::Result<Url, InvalidBaseUrl>) -> Client {
+        let inner = self.connector();
+        // The token provider is only created once the Hyper client is built, so that it can share it
+        let auth = match self.oauth2 {
+            Some(oauth2) => Some(Arc::new(oauth2.default_hyper_client(inner.clone())) as _),
+            None => self.auth,
+        };
+
+        Client {
+            inner,
+            base_url,
+            default_headers: self.default_headers,
+            auth,
+            timeout: self.timeout,
+            read_timeout: self.read_timeout,
+        }
+    }
+
+    fn fail(&mut self, e: hyper::http::Error) {
+        if self.error.is_none() {
+            self.error = Some(e.into());
+        }
+    }
+}
+
+fn header<K, V>(
+    key: K,
+    value: V,
+) -> std::result::Result<(HeaderName, HeaderValue), hyper::http::Error>
+where
+    HeaderName: TryFrom<K>,
+    <HeaderName as TryFrom<K>>::Error: Into<hyper::http::Error>,
+    HeaderValue: TryFrom<V>,
+    <HeaderValue as TryFrom<V>>::Error: Into<hyper::http::Error>,
+{
+    let key = HeaderName::try_from(key).map_err(Into::into)?;
+    let value = HeaderValue::try_from(value).map_err(Into::into)?;
+    Ok((key, value))
+}
+
+/// Why the base url of a client was rejected.
+#[derive(Clone, Debug)]
+pub(crate) enum InvalidBaseUrl {
+    Parse(url::ParseError),
+    UnsupportedScheme(String),
+}
+
+impl From<InvalidBaseUrl> for Error {
+    fn from(e: InvalidBaseUrl) -> Self {
+        match e {
+            InvalidBaseUrl::Parse(e) => Error::Url(e),
+            InvalidBaseUrl::UnsupportedScheme(scheme) => Error::UnsupportedScheme(scheme),
+        }
+    }
+}
@@ -1,42 +1,41 @@
-use crate::error::{Error, Result};
-use crate::pagination::{PaginatedRequest, PaginationStream};
-use crate::request::{Request, RequestData};
-use base64::{engine::general_purpose::STANDARD, Engine};
+use crate::auth::{Authenticator, BasicAuth, BearerAuth, HeaderAuth, QueryAuth};
+use crate::builder::{ClientBuilder, InvalidBaseUrl};
+use crate::decoder::{self, Decoder};
+use crate::endpoint;
+use crate::error::{Error, ErrorResponse, Result, TimeoutPhase, TypedError};
+use crate::pagination::{ItemStream, PaginatedRequest, PaginationStream};
+use crate::request::{Request, RequestData, TypedErrorRequest, WithMeta};
+use crate::response::{FromResponse, RequestUri, Response};
 use futures::prelude::*;
 use hyper::header::{HeaderMap, HeaderName, HeaderValue};
 use hyper::{
-    body::{to_bytes, Body},
+    body::{to_bytes, Body, Bytes},
     client::HttpConnector,
     http::request::Builder,
-    Client as HyperClient,
+    Client as HyperClient, StatusCode,
 };
 use hyper_tls::HttpsConnector;
 use log::debug;
-use secrecy::Secret;
-use std::collections::HashMap;
-use std::convert::TryFrom;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tower::Service;
-
-#[derive(Clone)]
-enum Authorization {
-    Bearer(Secret<String>),
-    Basic(String, Option<Secret<String>>),
-    Query(HashMap<String, Secret<String>>),
-    Header(HeaderMap<HeaderValue>),
-}
+use url::Url;
 
 /// The main client used for making requests.
 ///
-/// `Client` stores an async Reqwest client as well as the associated
-/// base url and possible authorization details for the REST server.
+/// `Client` stores an async Hyper client as well as the associated
+/// base url and possible authorization details for the REST server. It is configured through a
+/// [`ClientBuilder`].
 #[derive(Clone)]
 pub struct Client {
-    inner: HyperClient<HttpsConnector<HttpConnector>, Body>,
-    base_url: String,
-    default_headers: HeaderMap<HeaderValue>,
-    auth: Option<Authorization>,
+    pub(crate) inner: HyperClient<HttpsConnector<HttpConnector>, Body>,
+    pub(crate) base_url: std::result::Result<Url, InvalidBaseUrl>,
+    pub(crate) default_headers: HeaderMap<HeaderValue>,
+    pub(crate) auth: Option<Arc<dyn Authenticator>>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
 }
 
 impl<R: Request + 'static> Service<R> for Client {
@@ -55,185 +54,271 @@ impl<R: Request + 'static> Service<R> for Client {
 }
 
 impl Client {
-    /// Create a new `Client`.
+    /// Create a new `Client` with the default configuration.
+    ///
+    /// If the base url is invalid, sending requests fails with the error. Use [`Client::builder`]
+    /// to handle it when creating the client instead.
     pub fn new<S: ToString>(base_url: S) -> Self {
-        let connector = HttpsConnector::new();
-        let client = HyperClient::builder().build(connector);
-
-        Self::from_hyper(client, base_url)
+        Self::builder(base_url.to_string()).build_lenient()
     }
 
     /// Create a new `Client` from an existing Hyper Client.
+    #[deprecated(note = "use `ClientBuilder::hyper_client` instead")]
     pub fn from_hyper<S: ToString>(
         inner: HyperClient<HttpsConnector<HttpConnector>>,
         base_url: S,
     ) -> Self {
-        Self {
-            inner,
-            base_url: base_url.to_string(),
-            default_headers: HeaderMap::default(),
-            auth: None,
-        }
+        Self::builder(base_url.to_string())
+            .hyper_client(inner)
+            .build_lenient()
+    }
+
+    /// Create a [`ClientBuilder`] for configuring a `Client`.
+    pub fn builder<S: AsRef<str>>(base_url: S) -> ClientBuilder {
+        ClientBuilder::new(base_url)
     }
 
     /// Enable bearer authentication for the client
+    #[deprecated(note = "use `ClientBuilder::bearer_auth` instead")]
     pub fn bearer_auth<S: ToString>(mut self, token: S) -> Self {
-        self.auth = Some(Authorization::Bearer(Secret::new(token.to_string())));
+        self.auth = Some(Arc::new(BearerAuth::new(token)));
         self
     }
 
     /// Enable basic authentication for the client
+    #[deprecated(note = "use `ClientBuilder::basic_auth` instead")]
     pub fn basic_auth<T: Into<Option<S>>, S: ToString>(mut self, user: S, pass: T) -> Self {
-        self.auth = Some(Authorization::Basic(
-            user.to_string(),
-            pass.into().map(|x| Secret::new(x.to_string())),
-        ));
+        self.auth = Some(Arc::new(BasicAuth::new(user, pass)));
         self
     }
 
     /// Enable query authentication for the client
+    #[deprecated(note = "use `ClientBuilder::query_auth` instead")]
     pub fn query_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
-        let pairs = pairs
-            .into_iter()
-            .map(|(k, v)| (k.to_string(), Secret::new(v.to_string())))
-            .collect();
-        self.auth = Some(Authorization::Query(pairs));
+        self.auth = Some(Arc::new(QueryAuth::new(pairs)));
         self
     }
 
     /// Enable custom header authentication for the client
+    ///
+    /// # Panics
+    ///
+    /// Panics if a header name or value is invalid. Use [`ClientBuilder::header_auth`] to handle
+    /// the error instead.
+    #[deprecated(note = "use `ClientBuilder::header_auth` instead")]
     pub fn header_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
         let mut map = HeaderMap::new();
         for (k, v) in pairs {
-            let k = k.to_string();
-            let v = v.to_string();
-            let mut header_value = HeaderValue::from_str(&v).expect("Failed to create HeaderValue");
-            header_value.set_sensitive(true);
             map.insert(
-                HeaderName::try_from(&k).expect("Failed to create HeaderName"),
-                header_value,
+                HeaderName::try_from(k.to_string()).expect("Failed to create HeaderName"),
+                HeaderValue::try_from(v.to_string()).expect("Failed to create HeaderValue"),
             );
         }
-        self.auth = Some(Authorization::Header(map));
+        self.auth = Some(Arc::new(HeaderAuth::new(map)));
         self
     }
 
+    /// Replace the headers sent with every request.
+    #[deprecated(note = "use `ClientBuilder::default_headers` instead")]
     pub fn default_headers(mut self, default_headers: HeaderMap<HeaderValue>) -> Self {
         self.default_headers = default_headers;
         self
     }
 
-    fn send_raw<R>(&self, req: hyper::Request<Body>) -> impl std::future::Future<Output = Result<R>>
-    where
-        R: for<'de> serde::Deserialize<'de>,
-    {
+    /// The base url, or the error it was rejected with.
+    fn base_url(&self) -> Result<&Url> {
+        self.base_url.as_ref().map_err(|e| e.clone().into())
+    }
+
+    fn send_raw<R: FromResponse>(
+        &self,
+        req: hyper::Request<Body>,
+        decoder: Arc<dyn Decoder>,
+    ) -> impl std::future::Future<Output = Result<R>> {
         debug!("Sending request: {:?}", req);
+        let read_timeout = self.read_timeout;
+        let uri = req.uri().clone();
         self.inner
             .request(req)
             .map_err(From::from)
-            .and_then(|mut res| async move {
+            .and_then(move |mut res| async move {
                 let status = res.status();
-                let body = to_bytes(res.body_mut()).await?;
                 if status.is_success() {
-                    serde_json::from_slice(&body).map_err(From::from)
-                } else if status.is_client_error() {
-                    Err(Error::ClientError(status, String::from_utf8(body.into())?))
+                    res.extensions_mut().insert(RequestUri(uri));
+                    let response = R::from_response(res, decoder);
+                    return with_timeout(read_timeout, TimeoutPhase::Body, response).await;
+                }
+                let (parts, body) = res.into_parts();
+                let body = to_bytes(body).map_err(From::from);
+                let body = with_timeout(read_timeout, TimeoutPhase::Body, body).await?;
+                let res = ErrorResponse::new(status, parts.headers, body);
+                if status.is_client_error() {
+                    Err(Error::ClientError(res))
                 } else {
-                    Err(Error::ServerError(status, String::from_utf8(body.into())?))
+                    Err(Error::ServerError(res))
                 }
             })
     }
 
     fn format_request<R: Request>(&self, request: &R) -> Result<hyper::Request<Body>> {
-        let endpoint = request.endpoint();
-        let endpoint = endpoint.trim_matches('/');
-        let url = format!("{}/{}", self.base_url, endpoint);
-
-        let mut headers = self.default_headers.clone();
-        headers.extend(request.headers());
-        let mut req = Builder::new().uri(&url).method(R::METHOD);
-        for header in headers {
-            req = req.header(header.0.expect("Always has a header name"), header.1);
+        let mut url = endpoint::join(self.base_url()?, &request.endpoint())?;
+        if let Some(query) = request.query() {
+            endpoint::append_query(&mut url, &query.to_string()?);
         }
 
-        req = {
-            use secrecy::ExposeSecret;
-            match &self.auth {
-                None => req,
-                Some(Authorization::Bearer(token)) => {
-                    let mut header_value =
-                        HeaderValue::from_str(&format!("Bearer {}", token.expose_secret()))
-                            .expect("Failed to create HeaderValue");
-                    header_value.set_sensitive(true);
-                    req.header("authorization", header_value)
-                }
-                Some(Authorization::Basic(user, pass)) => {
-                    let creds = format!(
-                        "{}:{}",
-                        user,
-                        pass.as_ref()
-                            .map(|x| x.expose_secret())
-                            .unwrap_or(&String::new())
-                    );
-                    let encoded = STANDARD.encode(creds);
-                    let mut header_value = HeaderValue::from_str(&format!("Basic {}", encoded))
-                        .expect("Failed to create HeaderValue");
-                    header_value.set_sensitive(true);
-                    req.header("authorization", header_value)
-                }
-                Some(Authorization::Query(pairs)) => {
-                    let pairs: HashMap<_, _> =
-                        pairs.iter().map(|(k, v)| (k, v.expose_secret())).collect();
-                    let query = serde_qs::to_string(&pairs)?;
-                    let url = if url.contains('?') {
-                        format!("{}&{}", url, query)
-                    } else {
-                        format!("{}?{}", url, query)
-                    };
-                    req.uri(url)
-                }
-                Some(Authorization::Header(pairs)) => {
-                    for (k, v) in pairs {
-                        req = req.header(k, v);
-                    }
-                    req
-                }
-            }
+        // Default headers may hold credentials, so they are only sent to the origin of the base url
+        let mut headers = if self.same_origin(&url) {
+            self.default_headers.clone()
+        } else {
+            HeaderMap::new()
         };
+        headers.extend(request.try_headers()?);
+        let mut req = Builder::new().method(R::METHOD);
+        if let Some(req_headers) = req.headers_mut() {
+            req_headers.extend(headers);
+        }
 
         let body = match request.data() {
             RequestData::Empty => Body::empty(),
             RequestData::Form(data) => {
-                req = req
-                    .header("content-type", "application/x-www-form-urlencoded")
-                    .uri(url);
+                req = req.header("content-type", "application/x-www-form-urlencoded");
                 let body = serde_urlencoded::to_string(data)?;
                 Body::from(body)
             }
             RequestData::Json(data) => {
-                req = req.header("content-type", "application/json").uri(url);
+                req = req.header("content-type", "application/json");
                 let bytes = serde_json::to_vec(&data)?;
                 Body::from(bytes)
             }
             RequestData::Query(data) => {
-                let query = serde_qs::to_string(data)?;
-                let url = if url.contains('?') {
-                    format!("{}&{}", url, query)
-                } else {
-                    format!("{}?{}", url, query)
-                };
-                req = req.uri(url);
+                endpoint::append_query(&mut url, &serde_qs::to_string(data)?);
                 Body::empty()
             }
+            RequestData::Multipart(form) => {
+                req = req.header("content-type", form.content_type());
+                form.into_body()
+            }
+            RequestData::Raw {
+                content_type,
+                bytes,
+            } => {
+                req = req.header("content-type", content_type.as_ref());
+                Body::from(bytes)
+            }
+            RequestData::Stream {
+                content_type,
+                stream,
+            } => {
+                req = req.header("content-type", content_type.as_ref());
+                Body::wrap_stream(stream)
+            }
         };
 
-        req.body(body).map_err(From::from)
+        req.uri(url.as_str()).body(body).map_err(From::from)
     }
 
     /// Send a single `Request`
     pub async fn send<R: Request>(&self, request: R) -> Result<R::Response> {
+        let timeout = request.timeout().or(self.timeout);
+        with_timeout(
+            timeout,
+            TimeoutPhase::Request,
+            self.send_authorized(request),
+        )
+        .await
+    }
+
+    async fn send_authorized<R: Request>(&self, request: R) -> Result<R::Response> {
+        let decoder = request.decoder();
         let req = self.format_request(&request)?;
-        self.send_raw(req).await
+        // Requests to other origins, such as links returned by the server, aren't authenticated so
+        // that credentials aren't leaked to other hosts
+        let same_origin =
+            Url::parse(&req.uri().to_string()).is_ok_and(|url| self.same_origin(&url));
+        let auth = match &self.auth {
+            Some(auth) if same_origin => auth,
+            _ => return self.send_raw::<R::Response>(req, decoder).await,
+        };
+
+        let (req, authenticated) = authenticate(auth.as_ref(), req).await?;
+        match self.send_raw::<R::Response>(req, decoder.clone()).await {
+            Err(e)
+                if e.status() == Some(StatusCode::UNAUTHORIZED)
+                    && auth.unauthorized(&authenticated).await =>
+            {
+                // The request is built again, as its body may have been consumed
+                let (req, _) = authenticate(auth.as_ref(), self.format_request(&request)?).await?;
+                self.send_raw::<R::Response>(req, decoder).await
+            }
+            res => res,
+        }
+    }
+
+    fn same_origin(&self, url: &Url) -> bool {
+        self.base_url()
+            .is_ok_and(|base_url| url.origin() == base_url.origin())
+    }
+
+    /// Send a single `Request`, returning the response along with its status, headers and version.
+    pub async fn send_with_meta<R: Request>(&self, request: R) -> Result<Response<R::Response>> {
+        self.send(WithMeta(request)).await
+    }
+
+    /// Send a single `Request`, decoding the payload of unsuccessful responses into the error body
+    /// of the request with [`TypedErrorRequest::error_decoder`]. Unsuccessful responses whose
+    /// payload can't be decoded are returned as [`TypedError::Other`].
+    pub async fn send_typed<R: TypedErrorRequest>(
+        &self,
+        request: R,
+    ) -> std::result::Result<R::Response, TypedError<R::ErrorBody>> {
+        let decoder = request.error_decoder();
+        self.send(request).await.map_err(|e| {
+            let body = e
+                .error_response()
+                .and_then(|res| decoder::decode(decoder.as_ref(), res.body()).ok());
+            match body {
+                Some(body) => TypedError::Body(body, e),
+                None => TypedError::Other(e),
+            }
+        })
+    }
+}
+
+/// Authenticate a request, returning it along with the request passed to the authenticator. The
+/// body is only buffered if the authenticator needs it.
+async fn authenticate(
+    auth: &dyn Authenticator,
+    req: hyper::Request<Body>,
+) -> Result<(hyper::Request<Body>, hyper::Request<Bytes>)> {
+    let (parts, body) = req.into_parts();
+    let (mut authenticated, body) = if auth.needs_body() {
+        let bytes = to_bytes(body).await?;
+        (
+            hyper::Request::from_parts(parts, bytes.clone()),
+            Body::from(bytes),
+        )
+    } else {
+        (hyper::Request::from_parts(parts, Bytes::new()), body)
+    };
+    auth.authenticate(&mut authenticated).await?;
+
+    let mut req = hyper::Request::new(body);
+    *req.method_mut() = authenticated.method().clone();
+    *req.uri_mut() = authenticated.uri().clone();
+    *req.version_mut() = authenticated.version();
+    *req.headers_mut() = authenticated.headers().clone();
+    Ok((req, authenticated))
+}
+
+async fn with_timeout<T, F>(timeout: Option<Duration>, phase: TimeoutPhase, future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .unwrap_or(Err(Error::Timeout(phase))),
+        None => future.await,
     }
 }
 
@@ -247,6 +332,19 @@ pub trait ServiceExt<R, T>: Service<R> {
     {
         PaginationStream::new(self, request)
     }
+
+    /// Paginate the request, yielding the individual items of each page as extracted by `items`.
+    fn paginate_items<F, I>(self, request: R, items: F) -> ItemStream<Self, T, R, F, I>
+    where
+        T: Clone,
+        R: Request<Response = <Self as Service<R>>::Response>,
+        R: PaginatedRequest<PaginationData = T>,
+        F: FnMut(R::Response) -> I,
+        I: IntoIterator,
+        Self: Sized,
+    {
+        ItemStream::new(PaginationStream::new(self, request), items)
+    }
 }
 
 impl<P, T, Request> ServiceExt<Request, P> for T
@@ -0,0 +1,414 @@
+//! Decoders for the bodies of responses.
+//!
+//! A [`Request`](crate::Request) selects the decoder of its response body with
+//! [`Request::decoder`](crate::Request::decoder). The decoders for the formats supported by the
+//! library are provided in this module, and other formats can be supported by implementing
+//! [`Decoder`].
+use crate::error::Result;
+use serde::de::{self, value::SeqDeserializer, DeserializeOwned, Deserializer, Visitor};
+use std::fmt::Debug;
+
+/// A format in which response bodies can be decoded.
+///
+/// Decoders hand a deserializer for the body to the [`Output`], which deserializes it into the
+/// response type of the request:
+///
+/// ```
+/// use tower_api_client::decoder::{Decoder, Output};
+/// use tower_api_client::Error;
+///
+/// /// JSON data prefixed with `)]}'` to prevent it from being evaluated as a script.
+/// #[derive(Debug)]
+/// struct PrefixedJson;
+///
+/// impl Decoder for PrefixedJson {
+///     fn decode(&self, body: &[u8], output: Output<'_>) -> Result<(), Error> {
+///         let body = body.strip_prefix(b")]}'").unwrap_or(body);
+///         let mut deserializer = serde_json::Deserializer::from_slice(body);
+///         output.deserialize(&mut deserializer)?;
+///         deserializer.end()?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Decoder: Debug + Send + Sync + 'static {
+    /// Decode the body, passing a deserializer for it to `output`.
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()>;
+}
+
+type Deserialize<'a> = dyn FnMut(&mut dyn erased_serde::Deserializer<'_>) -> std::result::Result<(), erased_serde::Error>
+    + 'a;
+
+/// The value a response body is decoded into, which is deserialized from the deserializer handed
+/// to it by the [`Decoder`].
+pub struct Output<'a>(&'a mut Deserialize<'a>);
+
+impl Output<'_> {
+    /// Deserialize the value from `deserializer`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0)(&mut deserializer).map_err(de::Error::custom)
+    }
+}
+
+impl Debug for Output<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Output")
+    }
+}
+
+/// Decode a response body into `T` with the given decoder.
+pub(crate) fn decode<T: DeserializeOwned>(decoder: &dyn Decoder, body: &[u8]) -> Result<T> {
+    let mut value = None;
+    decoder.decode(
+        body,
+        Output(&mut |deserializer| {
+            value = Some(erased_serde::deserialize(deserializer)?);
+            Ok(())
+        }),
+    )?;
+    value.ok_or_else(|| {
+        let msg = format!("{:?} did not decode the response body", decoder);
+        <de::value::Error as de::Error>::custom(msg).into()
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// JSON data. This is the default decoder of requests.
+pub struct Json;
+
+impl Decoder for Json {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        output.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Plain text. The body must be valid UTF-8 and is deserialized as a string.
+pub struct Text;
+
+impl Decoder for Text {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        let text = String::from_utf8(body.to_vec())?;
+        output.deserialize(de::value::StringDeserializer::<de::value::Error>::new(text))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Raw bytes. The body is deserialized as a byte buffer or a sequence of bytes.
+pub struct Bytes;
+
+impl Decoder for Bytes {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        output.deserialize(BytesDeserializer(body))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "xml")]
+#[derive(Clone, Copy, Debug, Default)]
+/// XML data.
+pub struct Xml;
+
+#[cfg(feature = "xml")]
+impl Decoder for Xml {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        let mut deserializer = quick_xml::de::Deserializer::from_reader(body);
+        output.deserialize(&mut deserializer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "msgpack")]
+#[derive(Clone, Copy, Debug, Default)]
+/// MessagePack data.
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl Decoder for MessagePack {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        let mut deserializer = rmp_serde::Deserializer::from_read_ref(body);
+        output.deserialize(&mut deserializer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cbor")]
+#[derive(Clone, Copy, Debug, Default)]
+/// CBOR data.
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Decoder for Cbor {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        // ciborium only deserializes into concrete types, so the body is decoded into a value
+        // first
+        let value: ciborium::Value = ciborium::de::from_reader(body)?;
+        let value =
+            serde_value::to_value(value).map_err(<de::value::Error as de::Error>::custom)?;
+        let deserializer = serde_value::ValueDeserializer::<de::value::Error>::new(value);
+        output.deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "csv")]
+#[derive(Clone, Copy, Debug)]
+/// CSV data. The body is deserialized as a sequence of records, such as a `Vec` of structs.
+///
+/// By default, the first row is read as the header, and the fields of each record are keyed by
+/// their header so that records can be deserialized into structs. Without a header, records are
+/// deserialized as sequences of fields, such as tuples.
+pub struct Csv {
+    delimiter: u8,
+    has_headers: bool,
+}
+
+#[cfg(feature = "csv")]
+impl Csv {
+    pub fn new() -> Self {
+        Self {
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+
+    /// Set the field delimiter. Defaults to `,`.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set whether the first row is a header. Defaults to `true`.
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Default for Csv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Decoder for Csv {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<()> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .from_reader(body);
+        let headers = if self.has_headers {
+            Some(reader.headers()?.clone())
+        } else {
+            None
+        };
+        let records = reader
+            .records()
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let records = records.iter().map(|record| csv_de::Record {
+            headers: headers.as_ref(),
+            record,
+        });
+        output.deserialize(SeqDeserializer::<_, de::value::Error>::new(records))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "csv")]
+mod csv_de {
+    use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+    use serde::de::{self, Deserializer, IntoDeserializer, Visitor};
+
+    /// A record, deserialized as a map keyed by the headers if there are any, and as a sequence
+    /// of fields otherwise.
+    pub(super) struct Record<'a> {
+        pub(super) headers: Option<&'a csv::StringRecord>,
+        pub(super) record: &'a csv::StringRecord,
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for Record<'_> {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    impl<'de> Deserializer<'de> for Record<'_> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.headers {
+                Some(headers) => {
+                    let fields = headers.iter().zip(self.record.iter().map(Field));
+                    MapDeserializer::new(fields).deserialize_any(visitor)
+                }
+                None => self.deserialize_seq(visitor),
+            }
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            SeqDeserializer::new(self.record.iter().map(Field)).deserialize_any(visitor)
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_seq(visitor)
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct map struct enum identifier
+            ignored_any
+        }
+    }
+
+    /// A field of a record. Fields are strings, which are parsed when deserialized as other
+    /// primitives.
+    struct Field<'a>(&'a str);
+
+    impl<'de> IntoDeserializer<'de, Error> for Field<'_> {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    macro_rules! parse {
+        ($($method:ident => $visit:ident,)*) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                    match self.0.trim().parse() {
+                        Ok(value) => visitor.$visit(value),
+                        Err(_) => Err(de::Error::invalid_value(
+                            de::Unexpected::Str(self.0),
+                            &visitor,
+                        )),
+                    }
+                }
+            )*
+        };
+    }
+
+    impl<'de> Deserializer<'de> for Field<'_> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_str(self.0)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            if self.0.is_empty() {
+                visitor.visit_none()
+            } else {
+                visitor.visit_some(self)
+            }
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.0
+                .into_deserializer()
+                .deserialize_enum(name, variants, visitor)
+        }
+
+        parse! {
+            deserialize_bool => visit_bool,
+            deserialize_i8 => visit_i8,
+            deserialize_i16 => visit_i16,
+            deserialize_i32 => visit_i32,
+            deserialize_i64 => visit_i64,
+            deserialize_i128 => visit_i128,
+            deserialize_u8 => visit_u8,
+            deserialize_u16 => visit_u16,
+            deserialize_u32 => visit_u32,
+            deserialize_u64 => visit_u64,
+            deserialize_u128 => visit_u128,
+            deserialize_f32 => visit_f32,
+            deserialize_f64 => visit_f64,
+            deserialize_char => visit_char,
+        }
+
+        serde::forward_to_deserialize_any! {
+            str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+            identifier ignored_any
+        }
+    }
+}
+
+/// A deserializer handing out a borrowed byte slice, either as bytes or as a sequence of `u8`, so
+/// that both byte buffers and `Vec<u8>` can be used as response types.
+struct BytesDeserializer<'a>(&'a [u8]);
+
+impl<'de> Deserializer<'de> for BytesDeserializer<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_bytes(self.0)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_byte_buf(self.0.to_vec())
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.0.iter().copied()))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes option unit unit_struct tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
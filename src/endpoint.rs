@@ -0,0 +1,84 @@
+use crate::error::{Error, Result};
+use crate::path::Path;
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+/// Build the URL of a request from the base URL of the client and the endpoint of the request.
+///
+/// A relative endpoint is appended to the path of the base URL, whether or not either has a
+/// leading or trailing slash, and its query is appended to the query of the base URL. An absolute
+/// `http` or `https` endpoint is used as is, and only gets the query of the base URL if it has the
+/// same origin, so that parameters such as API keys aren't sent to other hosts. Fragments are
+/// dropped, as they are never sent to the server.
+///
+/// Relative endpoints built from a template with `.` or `..` segments, which would escape the path
+/// they are appended to, or with unfilled placeholders are rejected. Literal endpoints are used as
+/// they are.
+pub(crate) fn join(base: &Url, endpoint: &Path<'_>) -> Result<Url> {
+    let template = endpoint.is_template();
+    let endpoint = endpoint.to_string();
+    let mut url = match Url::parse(&endpoint) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            if url.origin() == base.origin() {
+                let mut merged = url.clone();
+                merged.set_query(base.query());
+                append_query(&mut merged, url.query().unwrap_or_default());
+                merged
+            } else {
+                url
+            }
+        }
+        _ => {
+            let endpoint = endpoint.split('#').next().unwrap_or_default();
+            let (path, query) = match endpoint.split_once('?') {
+                Some((path, query)) => (path, Some(query)),
+                None => (endpoint, None),
+            };
+            if template {
+                check_path(path)?;
+            }
+            let mut url = base.clone();
+            let path = path.trim_start_matches('/');
+            if !path.is_empty() {
+                url.set_path(&format!("{}/{}", base.path().trim_end_matches('/'), path));
+            }
+            append_query(&mut url, query.unwrap_or_default());
+            url
+        }
+    };
+    url.set_fragment(None);
+    Ok(url)
+}
+
+fn check_path(path: &str) -> Result<()> {
+    if path.contains(['{', '}']) {
+        return Err(Error::InvalidEndpoint(format!(
+            "{} has an unfilled placeholder",
+            path
+        )));
+    }
+    let dot_segment = path.split('/').any(|segment| {
+        let segment = percent_decode_str(segment).decode_utf8_lossy();
+        segment == "." || segment == ".."
+    });
+    if dot_segment {
+        return Err(Error::InvalidEndpoint(format!(
+            "{} has a `.` or `..` segment",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// Append an encoded query string to the query of the URL.
+pub(crate) fn append_query(url: &mut Url, query: &str) {
+    let query = query.trim_matches('&');
+    if query.is_empty() {
+        return;
+    }
+    let merged = match url.query().filter(|existing| !existing.is_empty()) {
+        Some(existing) => format!("{}&{}", existing, query),
+        None => query.to_string(),
+    };
+    url.set_query(Some(&merged));
+}
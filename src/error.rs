@@ -1,10 +1,14 @@
+use hyper::body::Bytes;
+use hyper::header::{HeaderMap, InvalidHeaderValue};
+use hyper::StatusCode;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 /// Possible errors in the request-response lifecycle.
 pub enum Error {
     #[error("Hyper error: {0}")]
-    Hyper(#[from] hyper::Error),
+    Hyper(hyper::Error),
 
     #[error("Http error: {0}")]
     Http(#[from] hyper::http::Error),
@@ -18,14 +22,48 @@ pub enum Error {
     #[error("Serde error: {0}")]
     SerdeUrlEncoded(#[from] serde_urlencoded::ser::Error),
 
+    #[error("Serde error: {0}")]
+    SerdeValue(#[from] serde::de::value::Error),
+
+    #[cfg(feature = "xml")]
+    #[error("Serde error: {0}")]
+    SerdeXml(#[from] quick_xml::DeError),
+
+    #[cfg(feature = "msgpack")]
+    #[error("Serde error: {0}")]
+    SerdeMsgPack(#[from] rmp_serde::decode::Error),
+
+    #[cfg(feature = "cbor")]
+    #[error("Serde error: {0}")]
+    SerdeCbor(#[from] ciborium::de::Error<std::io::Error>),
+
+    #[cfg(feature = "csv")]
+    #[error("Serde error: {0}")]
+    SerdeCsv(#[from] csv::Error),
+
+    #[error("Invalid url: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error("Unsupported url scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    #[error("Authentication error: {msg}")]
+    Authentication { msg: String },
+
     #[error("Pagination error: {msg}")]
     Pagination { msg: String },
 
-    #[error("Invalid request. Received status {0}. Message: {1}")]
-    ClientError(hyper::StatusCode, String),
+    #[error("Invalid request. Received status {}. Message: {}", .0.status(), .0.text())]
+    ClientError(ErrorResponse),
 
-    #[error("Server error. Received status {0}. Message: {1}")]
-    ServerError(hyper::StatusCode, String),
+    #[error("Server error. Received status {}. Message: {}", .0.status(), .0.text())]
+    ServerError(ErrorResponse),
+
+    #[error("Timed out during {0}")]
+    Timeout(TimeoutPhase),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -34,4 +72,144 @@ pub enum Error {
     Utf8(#[from] std::string::FromUtf8Error),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The phase of the request-response lifecycle in which a timeout occurred.
+pub enum TimeoutPhase {
+    /// Establishing a connection to the server.
+    Connect,
+    /// Completing the request as a whole.
+    Request,
+    /// Reading the body of the response.
+    Body,
+}
+
+impl fmt::Display for TimeoutPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutPhase::Connect => f.write_str("connect"),
+            TimeoutPhase::Request => f.write_str("request"),
+            TimeoutPhase::Body => f.write_str("body read"),
+        }
+    }
+}
+
+impl From<hyper::Error> for Error {
+    fn from(e: hyper::Error) -> Self {
+        if e.is_connect() {
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                    if io.kind() == std::io::ErrorKind::TimedOut {
+                        return Error::Timeout(TimeoutPhase::Connect);
+                    }
+                }
+                source = cause.source();
+            }
+        }
+        Error::Hyper(e)
+    }
+}
+
+impl From<InvalidHeaderValue> for Error {
+    fn from(e: InvalidHeaderValue) -> Self {
+        Error::Http(e.into())
+    }
+}
+
+impl Error {
+    /// The unsuccessful response returned by the server, if any.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self {
+            Error::ClientError(res) | Error::ServerError(res) => Some(res),
+            _ => None,
+        }
+    }
+
+    /// The status code of the unsuccessful response returned by the server, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.error_response().map(ErrorResponse::status)
+    }
+
+    /// The headers of the unsuccessful response returned by the server, if any.
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.error_response().map(ErrorResponse::headers)
+    }
+}
+
+#[derive(Debug)]
+/// An unsuccessful response from the server, along with its payload.
+pub struct ErrorResponse(Box<ErrorResponseInner>);
+
+#[derive(Debug)]
+struct ErrorResponseInner {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    text: String,
+}
+
+impl ErrorResponse {
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap, body: Bytes) -> Self {
+        let text = String::from_utf8_lossy(&body).into_owned();
+        Self(Box::new(ErrorResponseInner {
+            status,
+            headers,
+            body,
+            text,
+        }))
+    }
+
+    /// The status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.0.status
+    }
+
+    /// The headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.0.headers
+    }
+
+    /// The body of the response as text, with invalid UTF-8 replaced.
+    pub fn text(&self) -> &str {
+        &self.0.text
+    }
+
+    /// The raw body of the response.
+    pub fn body(&self) -> &Bytes {
+        &self.0.body
+    }
+}
+
+#[derive(Debug, Error)]
+/// Possible errors when sending a [`TypedErrorRequest`](crate::TypedErrorRequest) with
+/// [`Client::send_typed`](crate::Client::send_typed), where the payload of unsuccessful responses
+/// is decoded into the error body `E` of the request.
+pub enum TypedError<E> {
+    /// An unsuccessful response, whose payload was decoded into `E`. The error is either a
+    /// [`ClientError`](Error::ClientError) or a [`ServerError`](Error::ServerError).
+    #[error("{1}")]
+    Body(E, #[source] Error),
+
+    /// Any other error, including unsuccessful responses whose payload could not be decoded.
+    #[error(transparent)]
+    Other(#[from] Error),
+}
+
+impl<E> TypedError<E> {
+    /// The decoded error payload of the unsuccessful response returned by the server, if any.
+    pub fn body(&self) -> Option<&E> {
+        match self {
+            TypedError::Body(body, _) => Some(body),
+            TypedError::Other(_) => None,
+        }
+    }
+
+    /// The underlying error.
+    pub fn error(&self) -> &Error {
+        match self {
+            TypedError::Body(_, e) | TypedError::Other(e) => e,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,13 +1,30 @@
 //! tower_api_client is a library for building strongly typed API clients, with built-in capabilites
 //! for authentication, various request and response types and pagination.
+pub mod auth;
+mod builder;
 mod client;
+pub mod decoder;
+mod endpoint;
 mod error;
+pub mod multipart;
+pub mod oauth2;
 pub mod pagination;
+mod path;
 mod request;
+mod response;
+pub mod retry;
 
+pub use auth::Authenticator;
+pub use builder::ClientBuilder;
 pub use client::{Client, ServiceExt};
-pub use error::Error;
+pub use decoder::Decoder;
+pub use error::{Error, ErrorResponse, TimeoutPhase, TypedError};
+pub use hyper::body::Bytes;
 pub use hyper::header;
 pub use hyper::Method;
 pub use hyper::StatusCode;
+pub use path::Path;
 pub use request::*;
+pub use response::{BytesResponse, FromResponse, Response, StreamingBody};
+#[cfg(feature = "derive")]
+pub use tower_api_client_derive::Request;
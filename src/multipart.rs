@@ -0,0 +1,220 @@
+//! Constructs for building `multipart/form-data` request bodies.
+use futures::future::ready;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use futures::Stream;
+use hyper::body::{Body, Bytes};
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A `multipart/form-data` form, consisting of a number of named parts.
+pub struct Form {
+    boundary: String,
+    parts: Vec<(Cow<'static, str>, Part)>,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    /// Create a new, empty form with a randomly generated boundary.
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("{:016x}{:016x}", fastrand::u64(..), fastrand::u64(..)),
+            parts: Vec::new(),
+        }
+    }
+
+    /// The boundary separating the parts of the form.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Add a text field to the form.
+    pub fn text<N, V>(self, name: N, value: V) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+        V: Into<Cow<'static, str>>,
+    {
+        self.part(name, Part::text(value))
+    }
+
+    /// Add a field containing raw bytes to the form.
+    pub fn bytes<N, V>(self, name: N, value: V) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+        V: Into<Bytes>,
+    {
+        self.part(name, Part::bytes(value))
+    }
+
+    /// Add a file to the form. The file is streamed when the request is sent, and its name is
+    /// used as the filename of the part.
+    pub fn file<N, P>(self, name: N, path: P) -> io::Result<Self>
+    where
+        N: Into<Cow<'static, str>>,
+        P: AsRef<Path>,
+    {
+        Ok(self.part(name, Part::file(path)?))
+    }
+
+    /// Add a custom part to the form.
+    pub fn part<N: Into<Cow<'static, str>>>(mut self, name: N, part: Part) -> Self {
+        self.parts.push((name.into(), part));
+        self
+    }
+
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(crate) fn into_body(self) -> Body {
+        let boundary = self.boundary;
+        if self
+            .parts
+            .iter()
+            .all(|(_, part)| matches!(part.body, PartBody::Bytes(_)))
+        {
+            let mut body = Vec::new();
+            for (name, part) in self.parts {
+                body.extend_from_slice(&part.header(&boundary, &name));
+                if let PartBody::Bytes(bytes) = part.body {
+                    body.extend_from_slice(&bytes);
+                }
+                body.extend_from_slice(b"\r\n");
+            }
+            body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+            return Body::from(body);
+        }
+
+        let closing = Bytes::from(format!("--{}--\r\n", boundary));
+        let parts = stream::iter(self.parts).flat_map(move |(name, part)| {
+            let header = stream::once(ready(Ok(part.header(&boundary, &name))));
+            let body = match part.body {
+                PartBody::Bytes(bytes) => stream::once(ready(Ok(bytes))).boxed(),
+                PartBody::Stream(stream) => stream,
+            };
+            let crlf = stream::once(ready(Ok(Bytes::from_static(b"\r\n"))));
+            header.chain(body).chain(crlf)
+        });
+        Body::wrap_stream(parts.chain(stream::once(ready(Ok::<_, BoxError>(closing)))))
+    }
+}
+
+impl fmt::Debug for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Form")
+            .field("boundary", &self.boundary)
+            .field("parts", &self.parts)
+            .finish()
+    }
+}
+
+enum PartBody {
+    Bytes(Bytes),
+    Stream(BoxStream<'static, Result<Bytes, BoxError>>),
+}
+
+/// A single part of a multipart form.
+pub struct Part {
+    body: PartBody,
+    file_name: Option<Cow<'static, str>>,
+    content_type: Option<Cow<'static, str>>,
+}
+
+impl Part {
+    fn new(body: PartBody) -> Self {
+        Self {
+            body,
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// Create a part containing text.
+    pub fn text<V: Into<Cow<'static, str>>>(value: V) -> Self {
+        let bytes = match value.into() {
+            Cow::Borrowed(value) => Bytes::from_static(value.as_bytes()),
+            Cow::Owned(value) => Bytes::from(value),
+        };
+        Self::new(PartBody::Bytes(bytes))
+    }
+
+    /// Create a part containing raw bytes.
+    pub fn bytes<V: Into<Bytes>>(value: V) -> Self {
+        Self::new(PartBody::Bytes(value.into()))
+    }
+
+    /// Create a part whose content is read from a stream when the request is sent.
+    pub fn stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<BoxError> + 'static,
+    {
+        Self::new(PartBody::Stream(stream.map_err(Into::into).boxed()))
+    }
+
+    /// Create a part streaming the contents of a file. The name of the file is used as the
+    /// filename of the part, and the content type defaults to `application/octet-stream`.
+    pub fn file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::from_std(std::fs::File::open(path)?);
+        let part = Self::stream(tokio_util::io::ReaderStream::new(file))
+            .content_type("application/octet-stream");
+        Ok(match path.file_name() {
+            Some(name) => part.file_name(name.to_string_lossy().into_owned()),
+            None => part,
+        })
+    }
+
+    /// Set the filename of the part.
+    pub fn file_name<S: Into<Cow<'static, str>>>(mut self, file_name: S) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Set the content type of the part.
+    pub fn content_type<S: Into<Cow<'static, str>>>(mut self, content_type: S) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    fn header(&self, boundary: &str, name: &str) -> Bytes {
+        let mut header = format!(
+            "--{}\r\ncontent-disposition: form-data; name=\"{}\"",
+            boundary,
+            escape(name)
+        );
+        if let Some(file_name) = &self.file_name {
+            header.push_str(&format!("; filename=\"{}\"", escape(file_name)));
+        }
+        if let Some(content_type) = &self.content_type {
+            header.push_str(&format!("\r\ncontent-type: {}", content_type));
+        }
+        header.push_str("\r\n\r\n");
+        Bytes::from(header)
+    }
+}
+
+impl fmt::Debug for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part")
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Escape a field name or filename for use in a quoted `content-disposition` parameter.
+fn escape(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
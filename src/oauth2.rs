@@ -0,0 +1,258 @@
+//! OAuth2 authentication, fetching access tokens from a token endpoint and renewing them before
+//! they expire.
+use crate::auth::{set_bearer, Authenticator};
+use crate::error::{Error, Result};
+use futures::future::BoxFuture;
+use hyper::body::{to_bytes, Body, Bytes};
+use hyper::client::{Client as HyperClient, HttpConnector};
+use hyper::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use hyper::Method;
+use hyper_tls::HttpsConnector;
+use log::debug;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+enum Grant {
+    ClientCredentials,
+    RefreshToken,
+}
+
+struct CachedToken {
+    access_token: Secret<String>,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+/// An OAuth2 token provider, supporting the client credentials and refresh token grants.
+///
+/// Tokens are cached and renewed shortly before they expire. The provider is shared between clones
+/// of the `Client` it is configured on, so that they all use the same token. A request rejected
+/// with `401 Unauthorized` is retried once with a freshly fetched token.
+pub struct OAuth2 {
+    http: OnceLock<HyperClient<HttpsConnector<HttpConnector>>>,
+    token_url: String,
+    client_id: String,
+    client_secret: Option<Secret<String>>,
+    grant: Grant,
+    scopes: Vec<String>,
+    refresh_margin: Duration,
+    state: Mutex<State>,
+}
+
+struct State {
+    token: Option<CachedToken>,
+    refresh_token: Option<Secret<String>>,
+}
+
+impl OAuth2 {
+    fn new(
+        token_url: String,
+        client_id: String,
+        grant: Grant,
+        refresh_token: Option<String>,
+    ) -> Self {
+        Self {
+            http: OnceLock::new(),
+            token_url,
+            client_id,
+            client_secret: None,
+            grant,
+            scopes: Vec::new(),
+            refresh_margin: Duration::from_secs(30),
+            state: Mutex::new(State {
+                token: None,
+                refresh_token: refresh_token.map(Secret::new),
+            }),
+        }
+    }
+
+    /// Fetch tokens using the client credentials grant.
+    pub fn client_credentials<U, I, S>(token_url: U, client_id: I, client_secret: S) -> Self
+    where
+        U: ToString,
+        I: ToString,
+        S: ToString,
+    {
+        Self::new(
+            token_url.to_string(),
+            client_id.to_string(),
+            Grant::ClientCredentials,
+            None,
+        )
+        .client_secret(client_secret)
+    }
+
+    /// Fetch tokens using the refresh token grant. If the token endpoint rotates the refresh
+    /// token, the new refresh token is used for subsequent renewals.
+    pub fn refresh_token<U, I, S>(token_url: U, client_id: I, refresh_token: S) -> Self
+    where
+        U: ToString,
+        I: ToString,
+        S: ToString,
+    {
+        Self::new(
+            token_url.to_string(),
+            client_id.to_string(),
+            Grant::RefreshToken,
+            Some(refresh_token.to_string()),
+        )
+    }
+
+    /// Set the client secret sent to the token endpoint.
+    pub fn client_secret<S: ToString>(mut self, client_secret: S) -> Self {
+        self.client_secret = Some(Secret::new(client_secret.to_string()));
+        self
+    }
+
+    /// Add a scope to request.
+    pub fn scope<S: ToString>(mut self, scope: S) -> Self {
+        self.scopes.push(scope.to_string());
+        self
+    }
+
+    /// Set how long before its expiry a token is renewed. Defaults to 30 seconds.
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Use an existing Hyper client to send requests to the token endpoint. When configured with
+    /// [`ClientBuilder::oauth2`](crate::ClientBuilder::oauth2), the Hyper client of the `Client`
+    /// is used by default, so that its connector options apply to the token endpoint as well.
+    pub fn hyper_client(mut self, client: HyperClient<HttpsConnector<HttpConnector>>) -> Self {
+        self.http = OnceLock::from(client);
+        self
+    }
+
+    /// Use the Hyper client of the `Client` unless another one has been set.
+    pub(crate) fn default_hyper_client(
+        self,
+        client: HyperClient<HttpsConnector<HttpConnector>>,
+    ) -> Self {
+        let _ = self.http.set(client);
+        self
+    }
+
+    /// Get a valid access token, fetching a new one if there is no cached token or it is about to
+    /// expire.
+    async fn token(&self) -> Result<Secret<String>> {
+        let mut state = self.state.lock().await;
+        if let Some(token) = &state.token {
+            let fresh = match token.expires_at {
+                Some(expires_at) => Instant::now() + self.refresh_margin < expires_at,
+                None => true,
+            };
+            if fresh {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        debug!("Fetching OAuth2 token from {}", self.token_url);
+        let response = self.fetch(state.refresh_token.as_ref()).await?;
+        let access_token = Secret::new(response.access_token);
+        if let Some(refresh_token) = response.refresh_token {
+            state.refresh_token = Some(Secret::new(refresh_token));
+        }
+        state.token = Some(CachedToken {
+            access_token: access_token.clone(),
+            expires_at: response
+                .expires_in
+                .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+        });
+        Ok(access_token)
+    }
+
+    /// Discard the cached token if it is the given, rejected token, so that the next call to
+    /// `token` fetches a new one.
+    async fn invalidate(&self, rejected: &str) {
+        let mut state = self.state.lock().await;
+        if let Some(token) = &state.token {
+            if token.access_token.expose_secret() == rejected {
+                state.token = None;
+            }
+        }
+    }
+
+    async fn fetch(&self, refresh_token: Option<&Secret<String>>) -> Result<TokenResponse> {
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        match self.grant {
+            Grant::ClientCredentials => form.push(("grant_type", "client_credentials")),
+            Grant::RefreshToken => {
+                let refresh_token = refresh_token.ok_or_else(|| Error::Authentication {
+                    msg: "No refresh token available".into(),
+                })?;
+                form.push(("grant_type", "refresh_token"));
+                form.push(("refresh_token", refresh_token.expose_secret()));
+            }
+        }
+        if let Some(client_secret) = &self.client_secret {
+            form.push(("client_secret", client_secret.expose_secret()));
+        }
+        let scope = self.scopes.join(" ");
+        if !scope.is_empty() {
+            form.push(("scope", &scope));
+        }
+
+        let req = hyper::Request::builder()
+            .method(Method::POST)
+            .uri(&self.token_url)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(Body::from(serde_urlencoded::to_string(form)?))?;
+        let http = self
+            .http
+            .get_or_init(|| HyperClient::builder().build(HttpsConnector::new()));
+        let res = http.request(req).await?;
+        let status = res.status();
+        let body = to_bytes(res.into_body()).await?;
+        if !status.is_success() {
+            return Err(Error::Authentication {
+                msg: format!(
+                    "Token endpoint returned status {}: {}",
+                    status,
+                    String::from_utf8_lossy(&body)
+                ),
+            });
+        }
+        serde_json::from_slice(&body).map_err(From::from)
+    }
+}
+
+impl Authenticator for OAuth2 {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let token = self.token().await?;
+            set_bearer(request, &token)
+        })
+    }
+
+    fn unauthorized<'a>(&'a self, request: &'a hyper::Request<Bytes>) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            let rejected = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            match rejected {
+                Some(rejected) => {
+                    debug!("Request was unauthorized, retrying with a new OAuth2 token");
+                    self.invalidate(rejected).await;
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+}
@@ -0,0 +1,378 @@
+use super::PaginatedRequest;
+use crate::decoder::Decoder;
+use crate::error::Result;
+use crate::path::Path;
+use crate::request::{Query, Request, RequestData};
+use hyper::header::HeaderMap;
+use hyper::Method;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+type Extract<R, T> = Arc<dyn Fn(&<R as Request>::Response) -> T + Send + Sync>;
+type SetCursor<R, C> = Arc<dyn Fn(&mut R, &C) + Send + Sync>;
+type SetOffset<R> = Arc<dyn Fn(&mut R, usize, usize) + Send + Sync>;
+type SetPage<R> = Arc<dyn Fn(&mut R, usize) + Send + Sync>;
+
+/// Implement `Request` for a pagination helper by delegating to the request it wraps.
+macro_rules! delegate_request {
+    ($name:ident<R $(, $param:ident)*>) => {
+        impl<R: Request $(, $param: Send)*> Request for $name<R $(, $param)*> {
+            type Data = R::Data;
+            type Response = R::Response;
+
+            const METHOD: Method = R::METHOD;
+
+            fn endpoint(&self) -> Path<'_> {
+                self.request.endpoint()
+            }
+
+            fn headers(&self) -> HeaderMap {
+                self.request.headers()
+            }
+
+            fn try_headers(&self) -> Result<HeaderMap> {
+                self.request.try_headers()
+            }
+
+            fn query(&self) -> Option<Query<'_>> {
+                self.request.query()
+            }
+
+            fn data(&self) -> RequestData<&Self::Data> {
+                self.request.data()
+            }
+
+            fn decoder(&self) -> Arc<dyn Decoder> {
+                self.request.decoder()
+            }
+
+            fn timeout(&self) -> Option<Duration> {
+                self.request.timeout()
+            }
+        }
+
+        impl<R: Request $(, $param)*> $name<R $(, $param)*> {
+            /// Consume the pagination, returning the wrapped request.
+            pub fn into_inner(self) -> R {
+                self.request
+            }
+        }
+    };
+}
+
+/// Paginates a request using an opaque cursor returned with each page.
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use tower_api_client::{Path, Request, RequestData};
+/// use tower_api_client::pagination::CursorPagination;
+///
+/// #[derive(Clone, Serialize)]
+/// struct ListEvents {
+///     cursor: Option<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Events {
+///     events: Vec<String>,
+///     next_cursor: Option<String>,
+/// }
+///
+/// impl Request for ListEvents {
+///     type Data = Self;
+///     type Response = Events;
+///
+///     fn endpoint(&self) -> Path<'_> {
+///         "/events".into()
+///     }
+///
+///     fn data(&self) -> RequestData<&Self> {
+///         RequestData::Query(self)
+///     }
+/// }
+///
+/// let request = CursorPagination::new(
+///     ListEvents { cursor: None },
+///     |events: &Events| events.next_cursor.clone(),
+///     |request: &mut ListEvents, cursor: &String| request.cursor = Some(cursor.clone()),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct CursorPagination<R: Request, C = String> {
+    request: R,
+    cursor: Option<C>,
+    next_cursor: Extract<R, Option<C>>,
+    prev_cursor: Option<Extract<R, Option<C>>>,
+    set_cursor: SetCursor<R, C>,
+}
+
+impl<R: Request, C> CursorPagination<R, C> {
+    /// Paginate the request, getting the cursor of the next page from each response with
+    /// `next_cursor` and setting it on the request with `set_cursor`. Pagination stops when
+    /// `next_cursor` returns `None`.
+    pub fn new<N, S>(request: R, next_cursor: N, set_cursor: S) -> Self
+    where
+        N: Fn(&R::Response) -> Option<C> + Send + Sync + 'static,
+        S: Fn(&mut R, &C) + Send + Sync + 'static,
+    {
+        Self {
+            request,
+            cursor: None,
+            next_cursor: Arc::new(next_cursor),
+            prev_cursor: None,
+            set_cursor: Arc::new(set_cursor),
+        }
+    }
+
+    /// Get the cursor of the previous page from each response, to allow for paginating
+    /// [backward](super::Direction::Backward).
+    pub fn prev_cursor<P>(mut self, prev_cursor: P) -> Self
+    where
+        P: Fn(&R::Response) -> Option<C> + Send + Sync + 'static,
+    {
+        self.prev_cursor = Some(Arc::new(prev_cursor));
+        self
+    }
+
+    /// Start at the given cursor instead of the first page.
+    pub fn cursor(mut self, cursor: C) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+delegate_request!(CursorPagination<R, C>);
+
+impl<R, C> PaginatedRequest for CursorPagination<R, C>
+where
+    R: Request + Clone,
+    C: Clone + Debug + Send,
+{
+    type PaginationData = C;
+
+    fn get_page(&self) -> Option<C> {
+        self.cursor.clone()
+    }
+
+    fn next_page(&self, _prev_page: Option<&C>, response: &R::Response) -> Option<C> {
+        (self.next_cursor)(response)
+    }
+
+    fn update_request(&mut self, page: &C) {
+        (self.set_cursor)(&mut self.request, page);
+        self.cursor = Some(page.clone());
+    }
+
+    fn prev_page(&self, _page: Option<&C>, response: &R::Response) -> Option<C> {
+        self.prev_cursor.as_ref()?(response)
+    }
+}
+
+/// Paginates a request using an offset and a limit on the number of items per page.
+///
+/// Pagination stops at the first page with fewer items than the limit, or once the total number
+/// of items is reached if it is known. Knowing the total allows for
+/// [prefetching](super::PaginationStream::prefetch) pages. When paginating
+/// [backward](super::Direction::Backward), pagination stops at offset 0.
+#[derive(Clone)]
+pub struct OffsetLimitPagination<R: Request> {
+    request: R,
+    offset: usize,
+    limit: usize,
+    page_len: Extract<R, usize>,
+    total: Option<Extract<R, usize>>,
+    set_offset: SetOffset<R>,
+}
+
+impl<R: Request> OffsetLimitPagination<R> {
+    /// Paginate the request in pages of `limit` items, getting the number of items in each
+    /// response with `page_len` and setting the offset and limit on the request with
+    /// `set_offset`.
+    pub fn new<L, S>(request: R, limit: usize, page_len: L, set_offset: S) -> Self
+    where
+        L: Fn(&R::Response) -> usize + Send + Sync + 'static,
+        S: Fn(&mut R, usize, usize) + Send + Sync + 'static,
+    {
+        Self {
+            request,
+            offset: 0,
+            limit,
+            page_len: Arc::new(page_len),
+            total: None,
+            set_offset: Arc::new(set_offset),
+        }
+    }
+
+    /// Start at the given offset instead of 0.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Get the total number of items from the responses, to stop paginating once all items have
+    /// been fetched.
+    pub fn total<T>(mut self, total: T) -> Self
+    where
+        T: Fn(&R::Response) -> usize + Send + Sync + 'static,
+    {
+        self.total = Some(Arc::new(total));
+        self
+    }
+}
+
+delegate_request!(OffsetLimitPagination<R>);
+
+impl<R: Request + Clone> PaginatedRequest for OffsetLimitPagination<R> {
+    type PaginationData = usize;
+
+    fn get_page(&self) -> Option<usize> {
+        Some(self.offset)
+    }
+
+    fn next_page(&self, prev_page: Option<&usize>, response: &R::Response) -> Option<usize> {
+        let len = (self.page_len)(response);
+        let next = prev_page.copied().unwrap_or(self.offset) + len;
+        let done = match &self.total {
+            Some(total) => next >= total(response) || len == 0,
+            None => len < self.limit || len == 0,
+        };
+        if done {
+            None
+        } else {
+            Some(next)
+        }
+    }
+
+    fn update_request(&mut self, page: &usize) {
+        (self.set_offset)(&mut self.request, *page, self.limit);
+        self.offset = *page;
+    }
+
+    fn remaining_pages(&self, page: Option<&usize>, response: &R::Response) -> Option<Vec<usize>> {
+        let total = self.total.as_ref()?(response);
+        let len = (self.page_len)(response);
+        if len == 0 {
+            return Some(Vec::new());
+        }
+        // Use the length of the page rather than the limit, in case the server caps the limit
+        let next = page.copied().unwrap_or(self.offset) + len;
+        Some((next..total).step_by(len).collect())
+    }
+
+    fn prev_page(&self, page: Option<&usize>, _response: &R::Response) -> Option<usize> {
+        let offset = page.copied().unwrap_or(self.offset);
+        (offset > 0).then(|| offset.saturating_sub(self.limit))
+    }
+}
+
+/// Paginates a request using page numbers.
+///
+/// Pagination stops after the last page, if it is known, or at the first empty page. Knowing the
+/// last page allows for [prefetching](super::PaginationStream::prefetch) pages. If neither
+/// [`last_page`](Self::last_page) nor [`page_len`](Self::page_len) is set, only the first page is
+/// fetched. When paginating [backward](super::Direction::Backward), pagination stops at the
+/// [lowest page](Self::lowest_page).
+#[derive(Clone)]
+pub struct PageNumberPagination<R: Request> {
+    request: R,
+    page: usize,
+    lowest_page: usize,
+    last_page: Option<Extract<R, usize>>,
+    page_len: Option<Extract<R, usize>>,
+    set_page: SetPage<R>,
+}
+
+impl<R: Request> PageNumberPagination<R> {
+    /// Paginate the request, setting the page number on the request with `set_page`. Pages are
+    /// numbered from 1, unless another first page is set.
+    pub fn new<S>(request: R, set_page: S) -> Self
+    where
+        S: Fn(&mut R, usize) + Send + Sync + 'static,
+    {
+        Self {
+            request,
+            page: 1,
+            lowest_page: 1,
+            last_page: None,
+            page_len: None,
+            set_page: Arc::new(set_page),
+        }
+    }
+
+    /// Start at the given page, e.g. 0 for APIs that number pages from 0.
+    pub fn first_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self.lowest_page = self.lowest_page.min(page);
+        self
+    }
+
+    /// Set the lowest page number, at which paginating backward stops. Defaults to 1, or to the
+    /// first page if it is lower.
+    pub fn lowest_page(mut self, page: usize) -> Self {
+        self.lowest_page = page;
+        self
+    }
+
+    /// Get the number of the last page from the responses. For APIs that number pages from 1, this
+    /// is the total number of pages.
+    pub fn last_page<L>(mut self, last_page: L) -> Self
+    where
+        L: Fn(&R::Response) -> usize + Send + Sync + 'static,
+    {
+        self.last_page = Some(Arc::new(last_page));
+        self
+    }
+
+    /// Get the number of items in the responses, to stop paginating at the first empty page.
+    pub fn page_len<L>(mut self, page_len: L) -> Self
+    where
+        L: Fn(&R::Response) -> usize + Send + Sync + 'static,
+    {
+        self.page_len = Some(Arc::new(page_len));
+        self
+    }
+}
+
+delegate_request!(PageNumberPagination<R>);
+
+impl<R: Request + Clone> PaginatedRequest for PageNumberPagination<R> {
+    type PaginationData = usize;
+
+    fn get_page(&self) -> Option<usize> {
+        Some(self.page)
+    }
+
+    fn next_page(&self, prev_page: Option<&usize>, response: &R::Response) -> Option<usize> {
+        let page = prev_page.copied().unwrap_or(self.page);
+        let last = match &self.last_page {
+            Some(last_page) => page >= last_page(response),
+            None => self.page_len.is_none(),
+        };
+        let empty = match &self.page_len {
+            Some(page_len) => page_len(response) == 0,
+            None => false,
+        };
+        if last || empty {
+            None
+        } else {
+            Some(page + 1)
+        }
+    }
+
+    fn update_request(&mut self, page: &usize) {
+        (self.set_page)(&mut self.request, *page);
+        self.page = *page;
+    }
+
+    fn remaining_pages(&self, page: Option<&usize>, response: &R::Response) -> Option<Vec<usize>> {
+        let last_page = self.last_page.as_ref()?(response);
+        let page = page.copied().unwrap_or(self.page);
+        Some((page + 1..=last_page).collect())
+    }
+
+    fn prev_page(&self, page: Option<&usize>, _response: &R::Response) -> Option<usize> {
+        let page = page.copied().unwrap_or(self.page);
+        (page > self.lowest_page).then(|| page - 1)
+    }
+}
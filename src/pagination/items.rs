@@ -0,0 +1,85 @@
+use super::{PaginatedRequest, PaginationStream};
+use futures::{ready, Stream, TryStreamExt};
+use pin_project_lite::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::Service;
+
+pin_project! {
+    /// A stream of the individual items of a paginated request, created with
+    /// [`ServiceExt::paginate_items`](crate::ServiceExt::paginate_items).
+    ///
+    /// Pages are requested as the items of the previous page are consumed.
+    pub struct ItemStream<Svc: Service<R>, T, R, F, I: IntoIterator> {
+        #[pin]
+        pages: PaginationStream<Svc, T, R>,
+        extract: F,
+        items: Option<I::IntoIter>,
+        remaining: Option<usize>,
+    }
+}
+
+impl<Svc, T, R, F, I> ItemStream<Svc, T, R, F, I>
+where
+    Svc: Service<R>,
+    I: IntoIterator,
+{
+    pub(crate) fn new(pages: PaginationStream<Svc, T, R>, extract: F) -> Self {
+        Self {
+            pages,
+            extract,
+            items: None,
+            remaining: None,
+        }
+    }
+
+    /// Stop after `max_items` items, without requesting any further pages.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.remaining = Some(max_items);
+        self
+    }
+}
+
+impl<Svc, T, R, F, I> ItemStream<Svc, T, R, F, I>
+where
+    Self: Stream<Item = Result<I::Item, Svc::Error>>,
+    Svc: Service<R>,
+    I: IntoIterator,
+{
+    /// Collect all items into a `Vec`, stopping at the first error.
+    pub async fn collect_all(self) -> Result<Vec<I::Item>, Svc::Error> {
+        self.try_collect().await
+    }
+}
+
+impl<Svc, T, R, F, I> Stream for ItemStream<Svc, T, R, F, I>
+where
+    Svc: Service<R>,
+    PaginationStream<Svc, T, R>: Stream<Item = Result<R::Response, Svc::Error>>,
+    R: PaginatedRequest<PaginationData = T>,
+    F: FnMut(R::Response) -> I,
+    I: IntoIterator,
+{
+    type Item = Result<I::Item, Svc::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if *this.remaining == Some(0) {
+                return Poll::Ready(None);
+            }
+            if let Some(item) = this.items.as_mut().and_then(|items| items.next()) {
+                if let Some(remaining) = this.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            match ready!(this.pages.as_mut().poll_next(cx)) {
+                Some(Ok(page)) => *this.items = Some((this.extract)(page).into_iter()),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
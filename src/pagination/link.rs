@@ -0,0 +1,190 @@
+use super::PaginatedRequest;
+use crate::decoder::Decoder;
+use crate::error::Result;
+use crate::path::Path;
+use crate::request::{Query, Request, RequestData};
+use crate::response::Response;
+use hyper::header::{HeaderMap, LINK};
+use hyper::{Method, Uri};
+use log::debug;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Paginates a request by following the `next` links in the `Link` header of responses, as
+/// described in [RFC 8288](https://www.rfc-editor.org/rfc/rfc8288) and used by e.g. the GitHub
+/// API. When paginating [backward](super::Direction::Backward), the `prev` links are followed
+/// instead.
+///
+/// Responses are returned along with their metadata as a [`Response`]. Relative links are resolved
+/// against the URL of the request they were returned for, and absolute links are followed through
+/// the same client, even if they point outside of its base url. Links to another origin than the
+/// base url are sent without the authentication and default headers of the client, so that
+/// credentials aren't leaked to other hosts. The query parameters of the wrapped
+/// request are only sent with the first request, as links are expected to contain the full query.
+#[derive(Clone, Debug)]
+pub struct LinkPagination<R> {
+    request: R,
+    next: Option<String>,
+}
+
+impl<R> LinkPagination<R> {
+    pub fn new(request: R) -> Self {
+        Self {
+            request,
+            next: None,
+        }
+    }
+
+    /// Consume the pagination, returning the wrapped request.
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: Request> Request for LinkPagination<R> {
+    type Data = R::Data;
+    type Response = Response<R::Response>;
+
+    const METHOD: Method = R::METHOD;
+
+    fn endpoint(&self) -> Path<'_> {
+        match &self.next {
+            Some(next) => next.as_str().into(),
+            None => self.request.endpoint(),
+        }
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn try_headers(&self) -> Result<HeaderMap> {
+        self.request.try_headers()
+    }
+
+    fn query(&self) -> Option<Query<'_>> {
+        match &self.next {
+            Some(_) => None,
+            None => self.request.query(),
+        }
+    }
+
+    fn data(&self) -> RequestData<&Self::Data> {
+        match (&self.next, self.request.data()) {
+            (Some(_), RequestData::Query(_)) => RequestData::Empty,
+            (_, data) => data,
+        }
+    }
+
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        self.request.decoder()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+}
+
+impl<R: Request + Clone> PaginatedRequest for LinkPagination<R> {
+    type PaginationData = String;
+
+    fn get_page(&self) -> Option<String> {
+        self.next.clone()
+    }
+
+    fn next_page(&self, _prev_page: Option<&String>, response: &Self::Response) -> Option<String> {
+        let next = find_link(&response.headers, "next")?;
+        resolve(&response.uri, next)
+    }
+
+    fn prev_page(&self, _page: Option<&String>, response: &Self::Response) -> Option<String> {
+        let prev = find_link(&response.headers, "prev")
+            .or_else(|| find_link(&response.headers, "previous"))?;
+        resolve(&response.uri, prev)
+    }
+
+    fn update_request(&mut self, page: &String) {
+        self.next = Some(page.clone());
+    }
+}
+
+fn resolve(uri: &Uri, link: &str) -> Option<String> {
+    let resolved = match Url::parse(&uri.to_string()) {
+        Ok(base) => base.join(link),
+        Err(_) => Url::parse(link),
+    };
+    match resolved {
+        Ok(url) => Some(url.into()),
+        Err(e) => {
+            debug!("Failed to resolve link {} against {}: {}", link, uri, e);
+            None
+        }
+    }
+}
+
+/// Find the target of the first link with the given relation type, such as `next`, in the `Link`
+/// headers. The target is returned as is, and may be relative.
+pub fn find_link<'a>(headers: &'a HeaderMap, rel: &str) -> Option<&'a str> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(parse_links)
+        .find(|(_, params)| {
+            params.iter().any(|(name, value)| {
+                name.eq_ignore_ascii_case("rel")
+                    && value
+                        .split_whitespace()
+                        .any(|x| x.eq_ignore_ascii_case(rel))
+            })
+        })
+        .map(|(target, _)| target)
+}
+
+type Link<'a> = (&'a str, Vec<(&'a str, &'a str)>);
+
+/// Parse the links in a `Link` header into their targets and parameters. Parsing stops at the
+/// first malformed link.
+fn parse_links(value: &str) -> Vec<Link<'_>> {
+    let mut links = Vec::new();
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let Some(link) = rest.strip_prefix('<') else {
+            break;
+        };
+        let Some(end) = link.find('>') else {
+            break;
+        };
+        let target = &link[..end];
+        rest = &link[end + 1..];
+
+        let mut params = Vec::new();
+        while let Some(param) = rest.trim_start().strip_prefix(';') {
+            let param = param.trim_start();
+            let name_end = param
+                .find(|c: char| matches!(c, '=' | ';' | ',') || c.is_whitespace())
+                .unwrap_or(param.len());
+            let name = &param[..name_end];
+            rest = param[name_end..].trim_start();
+            let value = match rest.strip_prefix('=').map(str::trim_start) {
+                Some(quoted) if quoted.starts_with('"') => {
+                    let quoted = &quoted[1..];
+                    let end = quoted.find('"').unwrap_or(quoted.len());
+                    rest = quoted.get(end + 1..).unwrap_or("");
+                    &quoted[..end]
+                }
+                Some(token) => {
+                    let end = token.find([';', ',']).unwrap_or(token.len());
+                    rest = &token[end..];
+                    token[..end].trim_end()
+                }
+                None => "",
+            };
+            params.push((name, value));
+        }
+        links.push((target, params));
+    }
+    links
+}
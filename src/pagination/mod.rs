@@ -0,0 +1,443 @@
+//! Constructs for wrapping a paginated API.
+use crate::error::Error;
+use crate::request::{Request, WithMeta};
+use futures::stream::FuturesOrdered;
+use futures::{ready, Stream, StreamExt};
+use log::debug;
+use pin_project_lite::pin_project;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Sleep;
+use tower::Service;
+
+mod helpers;
+mod items;
+mod link;
+
+pub use helpers::{CursorPagination, OffsetLimitPagination, PageNumberPagination};
+pub use items::ItemStream;
+pub use link::{find_link, LinkPagination};
+
+/// A request which can be paginated.
+///
+/// `next_page` is given the decoded response. If the next page is found in the response headers,
+/// use a [`Response`](crate::Response) as the response type of the request to have access to
+/// them.
+///
+/// Pages can also be walked [backward](Direction::Backward) by implementing
+/// [`prev_page`](Self::prev_page).
+///
+/// Common pagination schemes are implemented by [`CursorPagination`], [`OffsetLimitPagination`],
+/// [`PageNumberPagination`] and [`LinkPagination`], which wrap a request that doesn't implement
+/// this trait itself.
+pub trait PaginatedRequest: Request + Clone {
+    type PaginationData;
+    fn get_page(&self) -> Option<Self::PaginationData>;
+    fn next_page(
+        &self,
+        prev_page: Option<&Self::PaginationData>,
+        response: &Self::Response,
+    ) -> Option<Self::PaginationData>;
+    fn update_request(&mut self, page: &Self::PaginationData);
+
+    /// The page preceding `page`, used when paginating [backward](Direction::Backward). Defaults
+    /// to `None`, so that only the first page is fetched when paginating backward.
+    fn prev_page(
+        &self,
+        _page: Option<&Self::PaginationData>,
+        _response: &Self::Response,
+    ) -> Option<Self::PaginationData> {
+        None
+    }
+
+    /// All pages following `page`, if they are known from its response, e.g. when the response
+    /// includes the total number of pages. This allows for
+    /// [prefetching](PaginationStream::prefetch) pages concurrently. Defaults to `None`.
+    fn remaining_pages(
+        &self,
+        _page: Option<&Self::PaginationData>,
+        _response: &Self::Response,
+    ) -> Option<Vec<Self::PaginationData>> {
+        None
+    }
+}
+
+impl<R: PaginatedRequest> PaginatedRequest for WithMeta<R> {
+    type PaginationData = R::PaginationData;
+
+    fn get_page(&self) -> Option<Self::PaginationData> {
+        self.0.get_page()
+    }
+
+    fn next_page(
+        &self,
+        prev_page: Option<&Self::PaginationData>,
+        response: &Self::Response,
+    ) -> Option<Self::PaginationData> {
+        self.0.next_page(prev_page, &response.body)
+    }
+
+    fn update_request(&mut self, page: &Self::PaginationData) {
+        self.0.update_request(page)
+    }
+
+    fn prev_page(
+        &self,
+        page: Option<&Self::PaginationData>,
+        response: &Self::Response,
+    ) -> Option<Self::PaginationData> {
+        self.0.prev_page(page, &response.body)
+    }
+
+    fn remaining_pages(
+        &self,
+        page: Option<&Self::PaginationData>,
+        response: &Self::Response,
+    ) -> Option<Vec<Self::PaginationData>> {
+        self.0.remaining_pages(page, &response.body)
+    }
+}
+
+pin_project! {
+    /// A stream of the pages of a paginated request, created with
+    /// [`ServiceExt::paginate`](crate::ServiceExt::paginate).
+    pub struct PaginationStream<Svc: Service<R>, T, R> {
+        state: State<T>,
+        svc: Svc,
+        #[pin]
+        future: Option<Svc::Future>,
+        request: R,
+        prefetch: usize,
+        direction: Direction,
+        queue: VecDeque<T>,
+        in_flight: FuturesOrdered<Svc::Future>,
+        in_flight_pages: VecDeque<T>,
+        policy: ErrorPolicy,
+        retries: u32,
+        delay: Option<Pin<Box<Sleep>>>,
+        retrying: bool,
+        stopped: bool,
+        detect_loop: Option<DetectLoop<T, Svc::Error>>,
+        repeated: Option<Svc::Error>,
+    }
+}
+
+/// Compares a page with the page following it, returning an error if they are the same page.
+type DetectLoop<T, E> = fn(&T, &T) -> Option<E>;
+
+impl<Svc: Service<R>, T, R: PaginatedRequest<PaginationData = T>> PaginationStream<Svc, T, R> {
+    pub(crate) fn new(svc: Svc, request: R) -> Self {
+        let page = request.get_page();
+        Self {
+            state: State::Start(page),
+            svc,
+            future: None,
+            request,
+            prefetch: 1,
+            direction: Direction::Forward,
+            queue: VecDeque::new(),
+            in_flight: FuturesOrdered::new(),
+            in_flight_pages: VecDeque::new(),
+            policy: ErrorPolicy::Stop,
+            retries: 0,
+            delay: None,
+            retrying: false,
+            stopped: false,
+            detect_loop: None,
+            repeated: None,
+        }
+    }
+
+    /// Keep up to `pages` requests in flight once all remaining pages are known, as returned by
+    /// [`PaginatedRequest::remaining_pages`]. Pages are still yielded in order. Until the remaining
+    /// pages are known, and for requests that can't tell them in advance, pages are requested one
+    /// at a time.
+    ///
+    /// Requests for the following pages may already be in flight when a page fails. They are
+    /// dropped unless the [error policy](Self::on_error) is [`ErrorPolicy::Skip`].
+    pub fn prefetch(mut self, pages: usize) -> Self {
+        self.prefetch = pages.max(1);
+        self
+    }
+
+    /// Set the direction in which to paginate from the first page. Defaults to
+    /// [`Direction::Forward`]. Pages are never prefetched when paginating backward.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set what to do when a page fails. Defaults to [`ErrorPolicy::Stop`].
+    pub fn on_error(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// End the stream with an [`Error::Pagination`] when the page following a page is the same
+    /// page, like a cursor that doesn't advance, instead of requesting it again forever. The page
+    /// is still yielded before the error.
+    pub fn detect_loops(mut self) -> Self
+    where
+        T: PartialEq + Debug,
+        Svc::Error: From<Error>,
+    {
+        self.detect_loop = Some(|page, next| {
+            (page == next).then(|| {
+                Error::Pagination {
+                    msg: format!("The page following {:?} is the same page", page),
+                }
+                .into()
+            })
+        });
+        self
+    }
+
+    /// A snapshot of the pagination state, pointing at the next page that has not been yielded
+    /// yet. The state can be saved, e.g. after processing each page, and later passed to
+    /// [`resume_from`](Self::resume_from) to continue where the stream left off. If the stream
+    /// stopped because of a failed page, the state points at that page.
+    pub fn state(&self) -> State<T>
+    where
+        T: Clone,
+    {
+        match self.in_flight_pages.front().or_else(|| self.queue.front()) {
+            Some(page) => State::Next(page.clone()),
+            None => self.state.clone(),
+        }
+    }
+
+    /// Continue pagination from a state returned by [`state`](Self::state). Must be called before
+    /// the stream is polled.
+    pub fn resume_from(mut self, state: State<T>) -> Self {
+        self.state = state;
+        self
+    }
+}
+
+impl<Svc, T, R> Stream for PaginationStream<Svc, T, R>
+where
+    T: Clone + Debug,
+    Svc: Service<R, Response = R::Response>,
+    R: PaginatedRequest<PaginationData = T>,
+{
+    type Item = Result<Svc::Response, Svc::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.stopped {
+            return Poll::Ready(None);
+        }
+        if let Some(e) = this.repeated.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        if *this.retrying || !this.queue.is_empty() || !this.in_flight.is_empty() {
+            if *this.retrying {
+                if let Some(delay) = this.delay.as_mut() {
+                    ready!(delay.as_mut().poll(cx));
+                    *this.delay = None;
+                }
+                if let Err(e) = ready!(this.svc.poll_ready(cx)) {
+                    return Poll::Ready(Some(Err(e)));
+                }
+                // The failed page is kept at the front while waiting to retry it
+                let page = this.in_flight_pages.front().expect("Retrying a page");
+                let mut request = this.request.clone();
+                request.update_request(page);
+                this.in_flight.push_front(this.svc.call(request));
+                *this.retrying = false;
+            }
+            while this.in_flight.len() < *this.prefetch && !this.queue.is_empty() {
+                match this.svc.poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {
+                        let page = this.queue.pop_front().expect("Queue is not empty");
+                        let mut request = this.request.clone();
+                        request.update_request(&page);
+                        this.in_flight.push_back(this.svc.call(request));
+                        this.in_flight_pages.push_back(page);
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Pending => break,
+                }
+            }
+            return match this.in_flight.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(response))) => {
+                    this.in_flight_pages.pop_front();
+                    *this.retries = 0;
+                    Poll::Ready(Some(Ok(response)))
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    if let Some(delay) = this.policy.retry_delay(*this.retries) {
+                        *this.retries += 1;
+                        debug!(
+                            "Failed to fetch page {:?}. Retrying in {:?}",
+                            this.in_flight_pages.front(),
+                            delay
+                        );
+                        *this.delay = Some(Box::pin(tokio::time::sleep(delay)));
+                        *this.retrying = true;
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                    *this.retries = 0;
+                    let page = this
+                        .in_flight_pages
+                        .pop_front()
+                        .expect("A page was in flight");
+                    if *this.policy != ErrorPolicy::Skip {
+                        this.queue.clear();
+                        this.in_flight_pages.clear();
+                        *this.in_flight = FuturesOrdered::new();
+                        *this.state = State::Next(page);
+                        *this.stopped = true;
+                    }
+                    Poll::Ready(Some(Err(e)))
+                }
+                // The service isn't ready to take the next request, and will wake us when it is
+                Poll::Ready(None) => Poll::Pending,
+                Poll::Pending => Poll::Pending,
+            };
+        }
+
+        let page = match this.state {
+            State::Start(None) => None,
+            State::Start(Some(state)) | State::Next(state) => Some(state.clone()),
+            State::End => {
+                return Poll::Ready(None);
+            }
+        };
+
+        loop {
+            match this.future.as_mut().as_pin_mut() {
+                Some(fut) => {
+                    let response = ready!(fut.poll(cx));
+                    // The future has completed, so we replace it with none to make sure it doesn't
+                    // get polled again
+                    this.future.set(None);
+                    let response = match response {
+                        Ok(response) => response,
+                        Err(e) => {
+                            if let Some(delay) = this.policy.retry_delay(*this.retries) {
+                                *this.retries += 1;
+                                debug!("Failed to fetch page {:?}. Retrying in {:?}", page, delay);
+                                *this.delay = Some(Box::pin(tokio::time::sleep(delay)));
+                                continue;
+                            }
+                            // Without a response, the following page is unknown and the stream
+                            // can't skip ahead. The state still points at the failed page.
+                            *this.retries = 0;
+                            *this.stopped = true;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    };
+                    *this.retries = 0;
+                    if *this.prefetch > 1 && *this.direction == Direction::Forward {
+                        if let Some(pages) = this.request.remaining_pages(page.as_ref(), &response)
+                        {
+                            this.queue.extend(pages);
+                            *this.state = State::End;
+                            return Poll::Ready(Some(Ok(response)));
+                        }
+                    }
+                    let next = match this.direction {
+                        Direction::Forward => this.request.next_page(page.as_ref(), &response),
+                        Direction::Backward => this.request.prev_page(page.as_ref(), &response),
+                    };
+                    let repeated = match (this.detect_loop, &page, &next) {
+                        (Some(detect_loop), Some(page), Some(next)) => detect_loop(page, next),
+                        _ => None,
+                    };
+                    if repeated.is_some() {
+                        // Yield this page, and report the loop instead of requesting it again
+                        *this.repeated = repeated;
+                        *this.state = State::End;
+                    } else if let Some(page) = next {
+                        *this.state = State::Next(page)
+                    } else {
+                        *this.state = State::End
+                    }
+
+                    return Poll::Ready(Some(Ok(response)));
+                }
+                None => {
+                    if let Some(delay) = this.delay.as_mut() {
+                        ready!(delay.as_mut().poll(cx));
+                        *this.delay = None;
+                    }
+                    if let Err(e) = ready!(this.svc.poll_ready(cx)) {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+
+                    if let Some(page) = page.as_ref() {
+                        this.request.update_request(page);
+                    }
+
+                    this.future.set(Some(this.svc.call(this.request.clone())));
+                }
+            }
+        }
+    }
+}
+
+/// The direction in which a [`PaginationStream`] paginates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow [`PaginatedRequest::next_page`].
+    #[default]
+    Forward,
+    /// Follow [`PaginatedRequest::prev_page`].
+    Backward,
+}
+
+/// What a [`PaginationStream`] does when a page fails.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Yield the error and end the stream.
+    #[default]
+    Stop,
+    /// Request the failed page again up to `max_retries` times before yielding the error and
+    /// ending the stream. The first retry waits for `backoff`, which doubles for each subsequent
+    /// retry.
+    Retry { max_retries: u32, backoff: Duration },
+    /// Yield the error and continue with the following page. The following page can only be known
+    /// without the response of the failed page when [prefetching](PaginationStream::prefetch), so
+    /// otherwise the stream ends as with [`Stop`](ErrorPolicy::Stop).
+    Skip,
+}
+
+impl ErrorPolicy {
+    /// The delay before the next retry, or `None` if the page should not be retried.
+    fn retry_delay(&self, retries: u32) -> Option<Duration> {
+        match self {
+            ErrorPolicy::Retry {
+                max_retries,
+                backoff,
+            } if retries < *max_retries => {
+                Some(backoff.saturating_mul(2u32.saturating_pow(retries)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// The current pagination state, which can be serialized to checkpoint pagination.
+pub enum State<T> {
+    /// State associated with the initial request.
+    Start(Option<T>),
+    /// State associated with continuing pagination.
+    Next(T),
+    /// State denoting that the last page has been reached.
+    End,
+}
+
+impl<T> Default for State<T> {
+    fn default() -> State<T> {
+        State::Start(None)
+    }
+}
@@ -0,0 +1,115 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Characters which are percent-encoded in path parameters: everything which isn't allowed in a
+/// path segment, as well as `/` and `%`, so that a parameter always stays a single segment.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// The endpoint of a request, returned from [`Request::endpoint`](crate::Request::endpoint).
+///
+/// Strings are converted into literal paths with `into`, which are sent as they are. Paths with
+/// parameters, such as IDs, are built from a template, in which the parameters are interpolated as
+/// percent-encoded path segments:
+///
+/// ```
+/// use tower_api_client::Path;
+///
+/// let path = Path::new("/users/{id}/posts").param("id", "jane doe/1");
+/// assert_eq!(path.to_string(), "/users/jane%20doe%2F1/posts");
+/// ```
+///
+/// Placeholders without a parameter are left as they are. Sending a request to a template with
+/// such a placeholder fails with [`Error::InvalidEndpoint`](crate::Error::InvalidEndpoint), as does
+/// sending it with a parameter which is `.` or `..`, since those would change the path rather than
+/// being a segment of it. Literal paths aren't checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<'a> {
+    template: Cow<'a, str>,
+    /// The parameters of a template, or `None` for a literal path.
+    params: Option<Vec<(String, String)>>,
+}
+
+impl<'a> Path<'a> {
+    /// Create a path from a template, in which parameters are named in braces, like `{id}`.
+    pub fn new<T: Into<Cow<'a, str>>>(template: T) -> Self {
+        Self {
+            template: template.into(),
+            params: Some(Vec::new()),
+        }
+    }
+
+    /// Set the value of a parameter, formatted with its `Display` implementation.
+    pub fn param<V: fmt::Display>(mut self, name: &str, value: V) -> Self {
+        let value = utf8_percent_encode(&value.to_string(), SEGMENT).to_string();
+        self.params
+            .get_or_insert_with(Vec::new)
+            .push((name.to_string(), value));
+        self
+    }
+
+    /// Whether the path is built from a template, rather than being a literal path.
+    pub(crate) fn is_template(&self) -> bool {
+        self.params.is_some()
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(params) = &self.params else {
+            return f.write_str(&self.template);
+        };
+        let mut rest = &*self.template;
+        while let Some(start) = rest.find('{') {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            f.write_str(&rest[..start])?;
+            let name = &rest[start + 1..end];
+            match params.iter().rev().find(|(param, _)| param == name) {
+                Some((_, value)) => f.write_str(value)?,
+                None => f.write_str(&rest[start..=end])?,
+            }
+            rest = &rest[end + 1..];
+        }
+        f.write_str(rest)
+    }
+}
+
+impl<'a> From<&'a str> for Path<'a> {
+    fn from(path: &'a str) -> Self {
+        Cow::Borrowed(path).into()
+    }
+}
+
+impl From<String> for Path<'static> {
+    fn from(path: String) -> Self {
+        Cow::<str>::Owned(path).into()
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Path<'a> {
+    fn from(path: Cow<'a, str>) -> Self {
+        Self {
+            template: path,
+            params: None,
+        }
+    }
+}
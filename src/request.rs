@@ -1,10 +1,23 @@
+use crate::decoder::{Decoder, Json};
+use crate::error::{self, Error};
+use crate::multipart::Form;
+use crate::path::Path;
+use crate::response::{FromResponse, Response};
+use futures::stream::{BoxStream, Stream, StreamExt, TryStreamExt};
+use hyper::body::Bytes;
 use hyper::{header::HeaderMap, Method};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Additional data to be sent along with the request.
+#[derive(Default)]
 pub enum RequestData<T> {
     /// No additional data.
+    #[default]
     Empty,
     /// HTTP form data.
     Form(T),
@@ -12,11 +25,109 @@ pub enum RequestData<T> {
     Json(T),
     /// Query data.
     Query(T),
+    /// Multipart form data.
+    Multipart(Form),
+    /// Bytes sent as they are, for bodies which can't be serialized with `serde`, such as signed
+    /// payloads, NDJSON or protobuf.
+    Raw {
+        content_type: Cow<'static, str>,
+        bytes: Bytes,
+    },
+    /// A body streamed to the server as it is read, without buffering it in memory. Usually
+    /// created with [`RequestData::stream`].
+    ///
+    /// The stream is only buffered if the client has an [`Authenticator`](crate::Authenticator)
+    /// signing the body, as reported by
+    /// [`Authenticator::needs_body`](crate::Authenticator::needs_body).
+    Stream {
+        content_type: Cow<'static, str>,
+        stream: BodyStream,
+    },
 }
 
-impl<T> Default for RequestData<T> {
-    fn default() -> Self {
-        RequestData::Empty
+/// A stream of the chunks of a request body.
+pub type BodyStream = BoxStream<'static, Result<Bytes, Box<dyn StdError + Send + Sync>>>;
+
+impl<T> RequestData<T> {
+    /// Raw bytes with the given content type.
+    pub fn raw<C, B>(content_type: C, bytes: B) -> Self
+    where
+        C: Into<Cow<'static, str>>,
+        B: Into<Bytes>,
+    {
+        RequestData::Raw {
+            content_type: content_type.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    /// A body streamed with the given content type.
+    pub fn stream<C, S, E>(content_type: C, stream: S) -> Self
+    where
+        C: Into<Cow<'static, str>>,
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<Box<dyn StdError + Send + Sync>> + 'static,
+    {
+        RequestData::Stream {
+            content_type: content_type.into(),
+            stream: stream.map_err(Into::into).boxed(),
+        }
+    }
+}
+
+/// Query parameters sent along with the data of a request, such as a JSON body. They are serialized
+/// from any type implementing `Serialize`, the same way as [`RequestData::Query`].
+///
+/// ```
+/// # use serde::Serialize;
+/// # use tower_api_client::{Method, Path, Query, Request, RequestData};
+/// #[derive(Serialize)]
+/// struct Options {
+///     dry_run: bool,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct CreateItem {
+///     #[serde(skip)]
+///     options: Options,
+///     name: String,
+/// }
+///
+/// impl Request for CreateItem {
+///     type Data = Self;
+///     type Response = ();
+///
+///     const METHOD: Method = Method::POST;
+///
+///     fn endpoint(&self) -> Path<'_> {
+///         "/items".into()
+///     }
+///
+///     fn query(&self) -> Option<Query<'_>> {
+///         Some(Query::new(&self.options))
+///     }
+///
+///     fn data(&self) -> RequestData<&Self> {
+///         RequestData::Json(self)
+///     }
+/// }
+/// ```
+pub struct Query<'a>(&'a dyn erased_serde::Serialize);
+
+impl<'a> Query<'a> {
+    /// Borrow the query parameters, which are serialized when the request is sent.
+    pub fn new<T: Serialize>(params: &'a T) -> Self {
+        Self(params)
+    }
+
+    pub(crate) fn to_string(&self) -> error::Result<String> {
+        serde_qs::to_string(&self.0).map_err(Error::from)
+    }
+}
+
+impl Debug for Query<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Query").finish_non_exhaustive()
     }
 }
 
@@ -25,15 +136,23 @@ impl<T> Default for RequestData<T> {
 pub trait Request: Send {
     /// The type of additional data sent with the request. Usually, this will be `()` or `Self`.
     type Data: Serialize;
-    /// The type of the response from the server.
-    type Response: for<'de> Deserialize<'de>;
+    /// The type of the response from the server. Any type implementing `Deserialize` can be used,
+    /// in which case the body is decoded with [`Request::decoder`].
+    type Response: FromResponse;
 
     /// The HTTP method for the request.
     const METHOD: Method = Method::GET;
 
     /// The endpoint to which the request will be sent. The base url is set in the client, and the
-    /// endpoint method returns the specific resource endpoint.
-    fn endpoint(&self) -> Cow<'_, str>;
+    /// endpoint method returns the specific resource endpoint. An absolute `http` or `https` URL,
+    /// such as a link returned by the server, is sent as is. If it points to another origin than the
+    /// base url, the request is sent without the authentication and default headers of the client.
+    ///
+    /// Static endpoints and links returned by the server are converted from strings with `into`,
+    /// and are sent as they are. Endpoints with parameters, such as IDs, are built from a template
+    /// with [`Path::new`], which percent-encodes the parameters and is validated when the request
+    /// is sent.
+    fn endpoint(&self) -> Path<'_>;
 
     /// Any additional headers that should be sent with the request. Note that common headers such
     /// as authorization headers should be set on the client directly.
@@ -41,10 +160,99 @@ pub trait Request: Send {
         Default::default()
     }
 
+    /// The headers of the request, for requests which build them from values that may not be valid
+    /// header values. An error is returned when the request is sent. Defaults to
+    /// [`Request::headers`].
+    fn try_headers(&self) -> error::Result<HeaderMap> {
+        Ok(self.headers())
+    }
+
+    /// Query parameters sent in addition to the request data, which allows sending them along with
+    /// a body. They are sent after any query in the endpoint, and before those of
+    /// [`RequestData::Query`].
+    fn query(&self) -> Option<Query<'_>> {
+        None
+    }
+
     /// The formatted request data.
     fn data(&self) -> RequestData<&Self::Data> {
         Default::default()
     }
+
+    /// The decoder of the response body, used to decode it into `Self::Response`. Defaults to
+    /// [`Json`]. See the [`decoder`](crate::decoder) module for the other built-in formats.
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(Json)
+    }
+
+    /// The timeout for the request as a whole, overriding the timeout set on the client. Only this
+    /// timeout can be overridden per request: the connect and read timeouts apply to every request
+    /// sent by the client.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Requests to APIs returning structured error payloads in unsuccessful responses. When such a
+/// request is sent with [`Client::send_typed`](crate::Client::send_typed), the payload is decoded
+/// into the error body of the request.
+pub trait TypedErrorRequest: Request {
+    /// The type of the error payload returned by the server for unsuccessful responses.
+    type ErrorBody: DeserializeOwned;
+
+    /// The decoder of error payloads, which may differ from the decoder of successful responses.
+    /// Defaults to [`Json`].
+    fn error_decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(Json)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Wrapper around a request, returning the response along with its status, headers and version
+/// as a [`Response`]. Can be sent through any service accepting the wrapped request.
+pub struct WithMeta<R>(pub R);
+
+impl<R: Request> Request for WithMeta<R> {
+    type Data = R::Data;
+    type Response = Response<R::Response>;
+
+    const METHOD: Method = R::METHOD;
+
+    fn endpoint(&self) -> Path<'_> {
+        self.0.endpoint()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.0.headers()
+    }
+
+    fn try_headers(&self) -> error::Result<HeaderMap> {
+        self.0.try_headers()
+    }
+
+    fn query(&self) -> Option<Query<'_>> {
+        self.0.query()
+    }
+
+    fn data(&self) -> RequestData<&Self::Data> {
+        self.0.data()
+    }
+
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        self.0.decoder()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.0.timeout()
+    }
+}
+
+impl<R: TypedErrorRequest> TypedErrorRequest for WithMeta<R> {
+    type ErrorBody = R::ErrorBody;
+
+    fn error_decoder(&self) -> Arc<dyn Decoder> {
+        self.0.error_decoder()
+    }
 }
 
 #[derive(Debug)]
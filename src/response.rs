@@ -0,0 +1,163 @@
+use crate::decoder::{self, Decoder};
+use crate::error::Result;
+use futures::future::BoxFuture;
+use futures::Stream;
+use hyper::body::{to_bytes, Body, Bytes, HttpBody};
+use hyper::header::HeaderMap;
+use hyper::{StatusCode, Uri, Version};
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Types that can be constructed from the response to a successful request.
+pub trait FromResponse: Sized + Send + 'static {
+    /// Construct the response type from the HTTP response. The `decoder` is the one returned by
+    /// [`Request::decoder`](crate::Request::decoder), and may be ignored by types that don't
+    /// decode the body.
+    fn from_response(
+        response: hyper::Response<Body>,
+        decoder: Arc<dyn Decoder>,
+    ) -> BoxFuture<'static, Result<Self>>;
+}
+
+impl<T> FromResponse for T
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_response(
+        response: hyper::Response<Body>,
+        decoder: Arc<dyn Decoder>,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(async move {
+            let body = to_bytes(response.into_body()).await?;
+            decoder::decode(decoder.as_ref(), &body)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A response from the server, containing the status, headers and HTTP version of the response
+/// alongside the decoded body.
+pub struct Response<T> {
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The HTTP version of the response.
+    pub version: Version,
+    /// The URI of the request this is a response to.
+    pub uri: Uri,
+    /// The decoded body of the response.
+    pub body: T,
+}
+
+impl<T> Response<T> {
+    /// Consume the response, returning the decoded body.
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    /// Map the body of the response, keeping the metadata intact.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Response<U> {
+        Response {
+            status: self.status,
+            headers: self.headers,
+            version: self.version,
+            uri: self.uri,
+            body: f(self.body),
+        }
+    }
+}
+
+/// The URI of the request, stored in the extensions of the response by the client.
+#[derive(Clone)]
+pub(crate) struct RequestUri(pub(crate) Uri);
+
+impl<T: FromResponse> FromResponse for Response<T> {
+    fn from_response(
+        response: hyper::Response<Body>,
+        decoder: Arc<dyn Decoder>,
+    ) -> BoxFuture<'static, Result<Self>> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let version = response.version();
+        let uri = response
+            .extensions()
+            .get::<RequestUri>()
+            .map(|uri| uri.0.clone())
+            .unwrap_or_default();
+        Box::pin(async move {
+            let body = T::from_response(response, decoder).await?;
+            Ok(Response {
+                status,
+                headers,
+                version,
+                uri,
+                body,
+            })
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The raw, fully buffered body of the response, regardless of its format.
+pub struct BytesResponse(Bytes);
+
+impl BytesResponse {
+    /// Consume the response, returning the underlying bytes.
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl Deref for BytesResponse {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromResponse for BytesResponse {
+    fn from_response(
+        response: hyper::Response<Body>,
+        _decoder: Arc<dyn Decoder>,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(async move { Ok(BytesResponse(to_bytes(response.into_body()).await?)) })
+    }
+}
+
+#[derive(Debug)]
+/// The body of the response as a stream of chunks, which are handed out as they are received
+/// instead of being buffered. Useful for large downloads.
+pub struct StreamingBody(Body);
+
+impl StreamingBody {
+    /// Consume the stream, returning the underlying Hyper body.
+    pub fn into_inner(self) -> Body {
+        self.0
+    }
+}
+
+impl Stream for StreamingBody {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_data(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map_err(From::from)))
+    }
+}
+
+impl FromResponse for StreamingBody {
+    fn from_response(
+        response: hyper::Response<Body>,
+        _decoder: Arc<dyn Decoder>,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(futures::future::ready(Ok(StreamingBody(
+            response.into_body(),
+        ))))
+    }
+}
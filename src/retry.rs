@@ -0,0 +1,217 @@
+//! A retry middleware aware of the semantics of the API.
+use crate::error::{Error, Result};
+use crate::request::Request;
+use futures::future::poll_fn;
+use hyper::header::RETRY_AFTER;
+use hyper::{Method, StatusCode};
+use log::debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+use tower::{Layer, Service};
+
+type Classifier = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// Layer retrying failed requests with exponential backoff.
+///
+/// By default, requests are attempted up to 3 times, and only idempotent requests (as determined
+/// by `Request::METHOD`) are retried. Server errors, `429 Too Many Requests` responses, timeouts
+/// and connection errors are considered retryable, and `Retry-After` headers are honored. Since a
+/// request may be sent several times, it must implement `Clone`.
+#[derive(Clone)]
+pub struct RetryLayer {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+    respect_retry_after: bool,
+    classifier: Classifier,
+}
+
+impl Default for RetryLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryLayer {
+    /// Create a new `RetryLayer` with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_non_idempotent: false,
+            respect_retry_after: true,
+            classifier: Arc::new(is_retryable),
+        }
+    }
+
+    /// Set the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry, which doubles for each subsequent retry up to
+    /// `max_delay`.
+    pub fn backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enable or disable randomizing the backoff delay between zero and its computed value.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Enable or disable retrying requests with non-idempotent methods, such as `POST`.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Enable or disable honoring `Retry-After` headers. A `Retry-After` exceeding the maximum
+    /// delay causes the error to be returned instead of retrying.
+    pub fn respect_retry_after(mut self, respect_retry_after: bool) -> Self {
+        self.respect_retry_after = respect_retry_after;
+        self
+    }
+
+    /// Set the function used to decide whether an error is retryable.
+    pub fn retry_if<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    fn is_idempotent(method: &Method) -> bool {
+        matches!(
+            *method,
+            Method::GET
+                | Method::HEAD
+                | Method::PUT
+                | Method::DELETE
+                | Method::OPTIONS
+                | Method::TRACE
+        )
+    }
+
+    /// The delay before the given retry, or `None` if the request should not be retried.
+    fn delay(&self, retry: u32, error: &Error) -> Option<Duration> {
+        if self.respect_retry_after {
+            if let Some(retry_after) = retry_after(error) {
+                return (retry_after <= self.max_delay).then_some(retry_after);
+            }
+        }
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry - 1))
+            .min(self.max_delay);
+        if self.jitter {
+            Some(backoff.mul_f64(fastrand::f64()))
+        } else {
+            Some(backoff)
+        }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Retry {
+            inner,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Service retrying failed requests. See [`RetryLayer`] for details.
+#[derive(Clone)]
+pub struct Retry<S> {
+    inner: S,
+    config: RetryLayer,
+}
+
+impl<S, R> Service<R> for Retry<S>
+where
+    S: Service<R, Error = Error> + Clone + Send + 'static,
+    S::Response: Send,
+    S::Future: Send,
+    R: Request + Clone + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = Pin<Box<dyn Send + Future<Output = Result<Self::Response>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        // The inner service has been driven to readiness, so we keep it for the first attempt and
+        // leave a fresh clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let config = self.config.clone();
+        let retryable_method = config.retry_non_idempotent || RetryLayer::is_idempotent(&R::METHOD);
+
+        Box::pin(async move {
+            let mut attempt = 1;
+            loop {
+                let error = match inner.call(request.clone()).await {
+                    Err(error) if retryable_method && attempt < config.max_attempts => error,
+                    result => return result,
+                };
+                if !(config.classifier)(&error) {
+                    return Err(error);
+                }
+                let delay = match config.delay(attempt, &error) {
+                    Some(delay) => delay,
+                    None => return Err(error),
+                };
+                debug!(
+                    "Attempt {} failed with error: {}. Retrying in {:?}",
+                    attempt, error, delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                poll_fn(|cx| inner.poll_ready(cx)).await?;
+            }
+        })
+    }
+}
+
+/// The default classification of retryable errors: server errors, `429 Too Many Requests`,
+/// timeouts and errors establishing a connection.
+pub fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::ServerError(_) => true,
+        Error::ClientError(res) => res.status() == StatusCode::TOO_MANY_REQUESTS,
+        Error::Hyper(e) => e.is_connect() || e.is_incomplete_message(),
+        Error::Timeout(_) => true,
+        _ => false,
+    }
+}
+
+/// Parse the `Retry-After` header of an error response, given either in seconds or as an HTTP date.
+fn retry_after(error: &Error) -> Option<Duration> {
+    let value = error.headers()?.get(RETRY_AFTER)?.to_str().ok()?;
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
@@ -9,7 +9,10 @@ async fn basic_auth() {
     let _ = env_logger::try_init();
     let server = MockServer::start().await;
     let uri = server.uri();
-    let client = Client::new(&uri).basic_auth("user", "pass");
+    let client = Client::builder(&uri)
+        .basic_auth("user", "pass")
+        .build()
+        .unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
@@ -26,7 +29,10 @@ async fn basic_auth_no_password() {
     let _ = env_logger::try_init();
     let server = MockServer::start().await;
     let uri = server.uri();
-    let client = Client::new(&uri).basic_auth("user", None);
+    let client = Client::builder(&uri)
+        .basic_auth("user", None)
+        .build()
+        .unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
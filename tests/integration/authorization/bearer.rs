@@ -9,7 +9,10 @@ async fn bearer_auth() {
     let _ = env_logger::try_init();
     let server = MockServer::start().await;
     let uri = server.uri();
-    let client = Client::new(&uri).bearer_auth("PASSWORD");
+    let client = Client::builder(&uri)
+        .bearer_auth("PASSWORD")
+        .build()
+        .unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
@@ -0,0 +1,163 @@
+use crate::utils::{JsonHello, NameGreeting};
+use futures::future::{self, BoxFuture};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_api_client::header::HeaderValue;
+use tower_api_client::{Authenticator, Bytes, Client, Error};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Signs requests with their method, path and body length.
+struct Signer;
+
+impl Authenticator for Signer {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let signature = format!(
+            "{} {} {}",
+            request.method(),
+            request.uri().path(),
+            request.body().len()
+        );
+        request
+            .headers_mut()
+            .insert("x-signature", HeaderValue::from_str(&signature).unwrap());
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn needs_body(&self) -> bool {
+        true
+    }
+}
+
+/// Records the length of the body it is given, without requesting it.
+struct BodyLength;
+
+impl Authenticator for BodyLength {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let length = request.body().len().to_string();
+        request
+            .headers_mut()
+            .insert("x-body-length", HeaderValue::from_str(&length).unwrap());
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+/// Rotates to a new key whenever a request is unauthorized.
+#[derive(Clone, Default)]
+struct RotatingKey(Arc<AtomicUsize>);
+
+impl Authenticator for RotatingKey {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut hyper::Request<Bytes>,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let key = format!("key-{}", self.0.load(Ordering::SeqCst));
+        request
+            .headers_mut()
+            .insert("x-api-key", HeaderValue::from_str(&key).unwrap());
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn unauthorized<'a>(&'a self, _request: &'a hyper::Request<Bytes>) -> BoxFuture<'a, bool> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Box::pin(future::ready(true))
+    }
+}
+
+#[tokio::test]
+async fn signs_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri).authenticator(Signer).build().unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("X-Signature", "GET /hello 16"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hello"})))
+        .mount(&server)
+        .await;
+
+    let response = client
+        .oneshot(JsonHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hello".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn body_not_buffered() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .authenticator(BodyLength)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("X-Body-Length", "0"))
+        .and(body_json(json!({"name": "world"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hello"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .oneshot(JsonHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn retries_unauthorized() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .authenticator(RotatingKey::default())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("X-Api-Key", "key-0"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("X-Api-Key", "key-1"))
+        .and(body_json(json!({"name": "world"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hello"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .oneshot(JsonHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+}
@@ -10,7 +10,7 @@ async fn header_auth() {
     let server = MockServer::start().await;
     let uri = server.uri();
     let auth = vec![("key", "k"), ("secret", "s")];
-    let client = Client::new(&uri).header_auth(auth);
+    let client = Client::builder(&uri).header_auth(auth).build().unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
@@ -0,0 +1,178 @@
+use crate::utils::upload;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tower::ServiceExt;
+use tower_api_client::auth::{HmacAuth, SignatureEncoding, TimestampFormat};
+use tower_api_client::{
+    Authenticator, Client, EmptyResponse, Error, Method, Path, Request, RequestData,
+};
+use wiremock::matchers::{body_string, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewOrder {
+    symbol: &'static str,
+    side: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    time_in_force: &'static str,
+    quantity: &'static str,
+    price: &'static str,
+    recv_window: u64,
+}
+
+impl Request for NewOrder {
+    type Data = Self;
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/api/v3/order".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Query(self)
+    }
+}
+
+#[derive(Serialize)]
+struct CreateHello {
+    name: &'static str,
+}
+
+impl Request for CreateHello {
+    type Data = Self;
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Json(self)
+    }
+}
+
+#[tokio::test]
+async fn signed_query() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    // Example from the Binance API documentation
+    let hmac = HmacAuth::new("NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j")
+        .template("{query}")
+        .clock(|| UNIX_EPOCH + Duration::from_millis(1499827319559))
+        .timestamp_query("timestamp")
+        .signature_query("signature");
+    let client = Client::builder(&uri).hmac_auth(hmac).build().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/order"))
+        .and(query_param("timestamp", "1499827319559"))
+        .and(query_param(
+            "signature",
+            "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    client
+        .oneshot(NewOrder {
+            symbol: "LTCBTC",
+            side: "BUY",
+            kind: "LIMIT",
+            time_in_force: "GTC",
+            quantity: "1",
+            price: "0.1",
+            recv_window: 5000,
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn signed_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let hmac = HmacAuth::new("secret")
+        .clock(|| UNIX_EPOCH + Duration::from_secs(1700000000))
+        .timestamp_format(TimestampFormat::Seconds)
+        .timestamp_header("ACCESS-TIMESTAMP")
+        .signature_header("ACCESS-SIGN")
+        .encoding(SignatureEncoding::Base64);
+    let client = Client::builder(&uri).hmac_auth(hmac).build().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/hello"))
+        .and(header("ACCESS-TIMESTAMP", "1700000000"))
+        .and(header(
+            "ACCESS-SIGN",
+            "iQUvXsTg9vfi2J9o8WKYc1pxOHT8WLxmM39VJXhq+OE=",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    client.oneshot(CreateHello { name: "world" }).await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_template() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let hmac = HmacAuth::new("secret")
+        .template("{method}{unknown}")
+        .clock(SystemTime::now);
+    let client = Client::builder(&uri).hmac_auth(hmac).build().unwrap();
+
+    assert!(matches!(
+        client.oneshot(CreateHello { name: "world" }).await,
+        Err(Error::Authentication { .. })
+    ));
+}
+
+#[test]
+fn needs_body() {
+    assert!(HmacAuth::new("secret").needs_body());
+    assert!(!HmacAuth::new("secret")
+        .template("{method}{path}")
+        .needs_body());
+    assert!(HmacAuth::new("secret")
+        .template("{method}")
+        .canonicalize(|input| input.method().to_string())
+        .needs_body());
+}
+
+#[tokio::test]
+async fn unsigned_body_stream() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let hmac = HmacAuth::new("secret")
+        .template("{method}{path}")
+        .clock(|| UNIX_EPOCH + Duration::from_secs(1700000000));
+    let client = Client::builder(&uri).hmac_auth(hmac).build().unwrap();
+
+    // The template doesn't sign the body, so it is streamed instead of being buffered
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("x-timestamp", "1700000000000"))
+        .and(header("transfer-encoding", "chunked"))
+        .and(|req: &wiremock::Request| {
+            req.headers
+                .keys()
+                .all(|name| !name.as_str().eq_ignore_ascii_case("content-length"))
+        })
+        .and(body_string("{\"id\":1}\n{\"id\":2}\n"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(upload(true)).await.unwrap();
+}
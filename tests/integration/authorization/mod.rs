@@ -1,4 +1,9 @@
 mod basic;
 mod bearer;
+mod custom;
 mod header;
+mod hmac;
+mod oauth2;
 mod query;
+#[cfg(feature = "sigv4")]
+mod sigv4;
@@ -0,0 +1,159 @@
+use crate::utils::EmptyHello;
+use serde_json::json;
+use tower::ServiceExt;
+use tower_api_client::oauth2::OAuth2;
+use tower_api_client::Client;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn token(access_token: &str, expires_in: u64) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": expires_in,
+    }))
+}
+
+#[tokio::test]
+async fn client_credentials() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let oauth2 = OAuth2::client_credentials(format!("{}/token", uri), "id", "secret").scope("read");
+    let client = Client::builder(&uri).oauth2(oauth2).build().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("client_id=id"))
+        .and(body_string_contains("client_secret=secret"))
+        .and(body_string_contains("scope=read"))
+        .respond_with(token("abc", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    // The token is cached and shared between clones of the client
+    client.clone().oneshot(EmptyHello).await.unwrap();
+    client.oneshot(EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn retries_unauthorized_with_new_token() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let oauth2 = OAuth2::client_credentials(format!("{}/token", uri), "id", "secret");
+    let client = Client::builder(&uri).oauth2(oauth2).build().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("revoked", 3600))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("abc", 3600))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer revoked"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn refresh_token() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let oauth2 = OAuth2::refresh_token(format!("{}/token", uri), "id", "first");
+    let client = Client::builder(&uri).oauth2(oauth2).build().unwrap();
+
+    // The first token expires immediately, and the refresh token is rotated
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .and(body_string_contains("refresh_token=first"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "abc",
+            "expires_in": 0,
+            "refresh_token": "second",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("refresh_token=second"))
+        .respond_with(token("def", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer def"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.clone().oneshot(EmptyHello).await.unwrap();
+    client.oneshot(EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn uses_client_connector() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let oauth2 = OAuth2::client_credentials(format!("{}/token", server.uri()), "id", "secret");
+    // The token endpoint is only served over HTTP, which the connector of the client refuses
+    let client = Client::builder("https://example.com")
+        .https_only(true)
+        .oauth2(oauth2)
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("abc", 3600))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    assert!(client.oneshot(EmptyHello).await.is_err());
+}
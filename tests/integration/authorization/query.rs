@@ -10,7 +10,7 @@ async fn query_auth() {
     let server = MockServer::start().await;
     let uri = server.uri();
     let auth = vec![("key", "k"), ("secret", "s")];
-    let client = Client::new(&uri).query_auth(auth);
+    let client = Client::builder(&uri).query_auth(auth).build().unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
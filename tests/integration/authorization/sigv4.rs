@@ -0,0 +1,194 @@
+//! Test vectors from the AWS Signature Version 4 test suite.
+use crate::utils::{upload, EmptyHello};
+use std::time::{Duration, UNIX_EPOCH};
+use tower::ServiceExt;
+use tower_api_client::auth::{AwsCredentials, SigV4Auth};
+use tower_api_client::header::{AUTHORIZATION, CONTENT_TYPE};
+use tower_api_client::{Authenticator, Bytes, Client};
+use wiremock::matchers::{body_string, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SESSION_TOKEN: &str = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+
+fn credentials() -> AwsCredentials {
+    AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY")
+}
+
+fn signer(credentials: AwsCredentials) -> SigV4Auth {
+    // 2015-08-30T12:36:00Z
+    SigV4Auth::new("us-east-1", "service", credentials)
+        .clock(|| UNIX_EPOCH + Duration::from_secs(1440938160))
+}
+
+async fn sign(auth: &SigV4Auth, request: hyper::Request<Bytes>) -> hyper::Request<Bytes> {
+    let mut request = request;
+    auth.authenticate(&mut request).await.unwrap();
+    assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+    request
+}
+
+fn assert_signature(request: &hyper::Request<Bytes>, signed_headers: &str, signature: &str) {
+    assert_eq!(
+        request.headers()[AUTHORIZATION],
+        format!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+            SignedHeaders={}, Signature={}",
+            signed_headers, signature
+        )
+    );
+}
+
+fn request(method: &str, uri: &str) -> hyper::http::request::Builder {
+    hyper::Request::builder().method(method).uri(uri)
+}
+
+#[tokio::test]
+async fn get_vanilla() {
+    let req = request("GET", "https://example.amazonaws.com/")
+        .body(Bytes::new())
+        .unwrap();
+    let req = sign(&signer(credentials()), req).await;
+    assert_eq!(req.headers()["host"], "example.amazonaws.com");
+    assert_signature(
+        &req,
+        "host;x-amz-date",
+        "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31",
+    );
+}
+
+#[tokio::test]
+async fn get_vanilla_query_order_key_case() {
+    let req = request(
+        "GET",
+        "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+    )
+    .body(Bytes::new())
+    .unwrap();
+    let req = sign(&signer(credentials()), req).await;
+    assert_signature(
+        &req,
+        "host;x-amz-date",
+        "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500",
+    );
+}
+
+#[tokio::test]
+async fn get_utf8() {
+    let req = request("GET", "https://example.amazonaws.com/%E1%88%B4")
+        .body(Bytes::new())
+        .unwrap();
+    let req = sign(&signer(credentials()), req).await;
+    assert_signature(
+        &req,
+        "host;x-amz-date",
+        "8318018e0b0f223aa2bbf98705b62bb787dc9c0e678f255a891fd03141be5d85",
+    );
+}
+
+#[tokio::test]
+async fn post_vanilla() {
+    let req = request("POST", "https://example.amazonaws.com/")
+        .body(Bytes::new())
+        .unwrap();
+    let req = sign(&signer(credentials()), req).await;
+    assert_signature(
+        &req,
+        "host;x-amz-date",
+        "5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b",
+    );
+}
+
+#[tokio::test]
+async fn post_x_www_form_urlencoded() {
+    let req = request("POST", "https://example.amazonaws.com/")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(Bytes::from("Param1=value1"))
+        .unwrap();
+    let req = sign(&signer(credentials()), req).await;
+    assert_signature(
+        &req,
+        "content-type;host;x-amz-date",
+        "ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a",
+    );
+}
+
+#[tokio::test]
+async fn post_sts_header_before() {
+    let req = request("POST", "https://example.amazonaws.com/")
+        .body(Bytes::new())
+        .unwrap();
+    let req = sign(&signer(credentials().session_token(SESSION_TOKEN)), req).await;
+    assert_eq!(req.headers()["x-amz-security-token"], SESSION_TOKEN);
+    assert_signature(
+        &req,
+        "host;x-amz-date;x-amz-security-token",
+        "85d96828115b5dc0cfc3bd16ad9e210dd772bbebba041836c64533a82be05ead",
+    );
+}
+
+#[tokio::test]
+async fn unsigned_payload() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let sigv4 = SigV4Auth::new("us-east-1", "s3", credentials()).unsigned_payload(true);
+    let client = Client::builder(&uri)
+        .user_agent("tower-api-client")
+        .sigv4_auth(sigv4)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("x-amz-content-sha256", "UNSIGNED-PAYLOAD"))
+        .and(|req: &wiremock::Request| {
+            // Header values are split on commas, so they are joined to check the value as a whole
+            let authorization = match req.headers.get(&"authorization".into()) {
+                Some(values) => values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => return false,
+            };
+            authorization.starts_with(
+                "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/",
+            ) && authorization.contains(
+                "/us-east-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, ",
+            )
+        })
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    client.oneshot(EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn unsigned_payload_stream() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let sigv4 = SigV4Auth::new("us-east-1", "s3", credentials()).unsigned_payload(true);
+    let client = Client::builder(server.uri())
+        .sigv4_auth(sigv4)
+        .build()
+        .unwrap();
+
+    // The payload isn't hashed, so the body is streamed instead of being buffered
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("x-amz-content-sha256", "UNSIGNED-PAYLOAD"))
+        .and(header("transfer-encoding", "chunked"))
+        .and(|req: &wiremock::Request| {
+            req.headers
+                .keys()
+                .all(|name| !name.as_str().eq_ignore_ascii_case("content-length"))
+        })
+        .and(body_string("{\"id\":1}\n{\"id\":2}\n"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(upload(true)).await.unwrap();
+}
@@ -0,0 +1,144 @@
+use crate::utils::EmptyHello;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use tower_api_client::header::{HeaderMap, HeaderValue, ACCEPT};
+use tower_api_client::{Client, ClientBuilder, Error};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Send a request with `client` to a raw server, returning the values of the `accept` headers it
+/// received. The mock server can't be used, as it only keeps one of the values of repeated headers.
+async fn received_accept(client: ClientBuilder, listener: TcpListener) -> Vec<String> {
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        let mut buf = [0; 1024];
+        while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            head.extend_from_slice(&buf[..n]);
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        String::from_utf8(head).unwrap()
+    });
+
+    client.build().unwrap().oneshot(EmptyHello).await.unwrap();
+    let head = server.await.unwrap();
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.eq_ignore_ascii_case("accept"))
+        .map(|(_, value)| value.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn invalid_base_url() {
+    assert!(matches!(
+        Client::builder("not a url").build(),
+        Err(Error::Url(_))
+    ));
+    assert!(matches!(
+        Client::builder("ftp://example.com").build(),
+        Err(Error::UnsupportedScheme(scheme)) if scheme == "ftp"
+    ));
+}
+
+#[tokio::test]
+async fn invalid_base_url_on_send() {
+    let _ = env_logger::try_init();
+    // `localhost` is parsed as the scheme of the url
+    let client = Client::new("localhost:8080");
+    assert!(matches!(
+        client.oneshot(EmptyHello).await,
+        Err(Error::UnsupportedScheme(scheme)) if scheme == "localhost"
+    ));
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn deprecated_setters() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).bearer_auth("token");
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("authorization", "Bearer token"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn repeated_header() {
+    let _ = env_logger::try_init();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = Client::builder(format!("http://{}", listener.local_addr().unwrap()))
+        .header("accept", "text/plain")
+        .header("accept", "application/json");
+
+    assert_eq!(
+        received_accept(client, listener).await,
+        ["text/plain", "application/json"]
+    );
+}
+
+#[tokio::test]
+async fn multi_valued_default_headers() {
+    let _ = env_logger::try_init();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut headers = HeaderMap::new();
+    headers.append(ACCEPT, HeaderValue::from_static("text/plain"));
+    headers.append(ACCEPT, HeaderValue::from_static("application/json"));
+    let client = Client::builder(format!("http://{}", listener.local_addr().unwrap()))
+        .default_headers(headers);
+
+    assert_eq!(
+        received_accept(client, listener).await,
+        ["text/plain", "application/json"]
+    );
+}
+
+#[test]
+fn invalid_header() {
+    assert!(matches!(
+        Client::builder("https://example.com")
+            .header("x-api-key", "line\nbreak")
+            .build(),
+        Err(Error::Http(_))
+    ));
+    assert!(matches!(
+        Client::builder("https://example.com")
+            .header_auth(vec![("invalid key", "secret")])
+            .build(),
+        Err(Error::Http(_))
+    ));
+}
+
+#[tokio::test]
+async fn user_agent_and_headers() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(format!("{}/", uri))
+        .user_agent("tower-api-client")
+        .header("x-client", "tests")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("User-Agent", "tower-api-client"))
+        .and(header("X-Client", "tests"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    client.oneshot(EmptyHello).await.unwrap();
+}
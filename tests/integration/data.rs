@@ -1,7 +1,9 @@
-use crate::utils::{FormHello, JsonHello, NameGreeting, QueryHello};
+use crate::utils::{
+    upload, FormHello, HelloOptions, JsonHello, NameGreeting, QueryHello, QueryJsonHello,
+};
 use serde_json::json;
 use tower::ServiceExt;
-use tower_api_client::Client;
+use tower_api_client::{Client, EmptyResponse, Error, Path, Query, Request};
 use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
@@ -77,6 +79,69 @@ async fn json() {
     );
 }
 
+#[tokio::test]
+async fn query_and_json() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(|req: &MockRequest| req.url.query() == Some("version=1&lang=sv"))
+        .and(body_json(json!({"name": "world"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hej, world!"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .oneshot(QueryJsonHello {
+            options: HelloOptions { lang: "sv".into() },
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hej, world!".into(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn invalid_query() {
+    struct ListQuery(u32);
+
+    impl Request for ListQuery {
+        type Data = ();
+        type Response = EmptyResponse;
+
+        fn endpoint(&self) -> Path<'_> {
+            "/hello".into()
+        }
+
+        fn query(&self) -> Option<Query<'_>> {
+            Some(Query::new(&self.0))
+        }
+    }
+
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let error = client.oneshot(ListQuery(1)).await.unwrap_err();
+    assert!(matches!(error, Error::SerdeQs(_)));
+}
+
 #[tokio::test]
 async fn form() {
     let _ = env_logger::try_init();
@@ -110,3 +175,69 @@ async fn form() {
         }
     );
 }
+
+#[tokio::test]
+async fn raw() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("content-type", "application/x-ndjson"))
+        .and(header("content-length", "18"))
+        .and(body_string("{\"id\":1}\n{\"id\":2}\n"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(upload(false)).await.unwrap();
+}
+
+#[tokio::test]
+async fn stream() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("content-type", "application/x-ndjson"))
+        .and(header("transfer-encoding", "chunked"))
+        .and(body_string("{\"id\":1}\n{\"id\":2}\n"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(upload(true)).await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_with_authenticator() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::builder(server.uri())
+        .header_auth(vec![("X-Api-Key", "secret")])
+        .build()
+        .unwrap();
+
+    // The body is streamed as is, as header authentication doesn't need it
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(header("x-api-key", "secret"))
+        .and(header("transfer-encoding", "chunked"))
+        .and(|req: &MockRequest| {
+            req.headers
+                .keys()
+                .all(|name| !name.as_str().eq_ignore_ascii_case("content-length"))
+        })
+        .and(body_string("{\"id\":1}\n{\"id\":2}\n"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(upload(true)).await.unwrap();
+}
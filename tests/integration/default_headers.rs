@@ -14,7 +14,10 @@ async fn default_header() {
     let uri = server.uri();
     let mut headers = HeaderMap::new();
     headers.insert("user-agent", HeaderValue::from_static("tower-api-client"));
-    let client = Client::new(&uri).default_headers(headers);
+    let client = Client::builder(&uri)
+        .default_headers(headers)
+        .build()
+        .unwrap();
 
     Mock::given(method("GET"))
         .and(path("/hello"))
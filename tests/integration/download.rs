@@ -0,0 +1,65 @@
+use futures::TryStreamExt;
+use tower::ServiceExt;
+use tower_api_client::{BytesResponse, Client, Path, Request, StreamingBody};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct Download;
+
+impl Request for Download {
+    type Data = ();
+    type Response = BytesResponse;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/download".into()
+    }
+}
+
+struct StreamingDownload;
+
+impl Request for StreamingDownload {
+    type Data = ();
+    type Response = StreamingBody;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/download".into()
+    }
+}
+
+#[tokio::test]
+async fn bytes_response() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/download"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 1024]))
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(Download).await.unwrap();
+    assert_eq!(&*response, &[1u8; 1024][..]);
+}
+
+#[tokio::test]
+async fn streaming_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/download"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 64 * 1024]))
+        .mount(&server)
+        .await;
+
+    let body = client.send(StreamingDownload).await.unwrap();
+    let len = body
+        .try_fold(0, |len, chunk| async move { Ok(len + chunk.len()) })
+        .await
+        .unwrap();
+    assert_eq!(len, 64 * 1024);
+}
@@ -1,9 +1,36 @@
 use crate::utils::EmptyHello;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
 use tower::ServiceExt;
-use tower_api_client::{Client, Error, StatusCode};
+use tower_api_client::decoder::{self, Decoder};
+use tower_api_client::{
+    Client, EmptyResponse, Error, Path, Request, StatusCode, TypedError, TypedErrorRequest,
+};
 use wiremock::matchers::any;
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
+#[derive(Deserialize, Debug, PartialEq)]
+struct ApiError {
+    code: u32,
+    message: String,
+}
+
+struct TypedHello;
+
+impl Request for TypedHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+}
+
+impl TypedErrorRequest for TypedHello {
+    type ErrorBody = ApiError;
+}
+
 #[tokio::test]
 async fn client_error() {
     let _ = env_logger::try_init();
@@ -18,7 +45,7 @@ async fn client_error() {
 
     assert!(matches!(
         client.oneshot(EmptyHello).await.unwrap_err(),
-        Error::ClientError(status, msg) if (status == StatusCode::NOT_FOUND && msg == String::new())
+        Error::ClientError(res) if (res.status() == StatusCode::NOT_FOUND && res.text().is_empty())
     ));
 }
 
@@ -36,6 +63,103 @@ async fn server_error() {
 
     assert!(matches!(
         client.oneshot(EmptyHello).await.unwrap_err(),
-        Error::ServerError(status, msg) if (status == StatusCode::INTERNAL_SERVER_ERROR && msg == String::new())
+        Error::ServerError(res) if (res.status() == StatusCode::INTERNAL_SERVER_ERROR && res.text().is_empty())
+    ));
+}
+
+#[tokio::test]
+async fn typed_error() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(422)
+                .insert_header("x-request-id", "abc")
+                .set_body_json(json!({"code": 7, "message": "Invalid name"})),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client.send_typed(TypedHello).await.unwrap_err();
+    assert_eq!(err.error().status(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+    assert_eq!(err.error().headers().unwrap()["x-request-id"], "abc");
+    assert_eq!(
+        err.body(),
+        Some(&ApiError {
+            code: 7,
+            message: "Invalid name".into()
+        })
+    );
+}
+
+#[tokio::test]
+async fn unparseable_typed_error() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(502).set_body_string("Bad gateway"))
+        .mount(&server)
+        .await;
+
+    let err = client.send_typed(TypedHello).await.unwrap_err();
+    assert!(
+        matches!(&err, TypedError::Other(Error::ServerError(res)) if res.text() == "Bad gateway")
+    );
+}
+
+#[derive(Debug)]
+struct TextError;
+
+impl<'de> Deserialize<'de> for TextError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        match text.as_str() {
+            "Try again later" => Ok(TextError),
+            _ => Err(serde::de::Error::custom("unknown error")),
+        }
+    }
+}
+
+struct TextErrorHello;
+
+impl Request for TextErrorHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+}
+
+impl TypedErrorRequest for TextErrorHello {
+    type ErrorBody = TextError;
+
+    fn error_decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(decoder::Text)
+    }
+}
+
+#[tokio::test]
+async fn error_decoder() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(503).set_body_string("Try again later"))
+        .mount(&server)
+        .await;
+
+    let err = client.send_typed(TextErrorHello).await.unwrap_err();
+    assert!(matches!(
+        err,
+        TypedError::Body(TextError, Error::ServerError(_))
     ));
 }
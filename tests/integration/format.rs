@@ -0,0 +1,258 @@
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_api_client::decoder::{self, Decoder, Output};
+use tower_api_client::{Client, Error, Path, Request};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct GetText;
+
+impl Request for GetText {
+    type Data = ();
+    type Response = String;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/text".into()
+    }
+
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(decoder::Text)
+    }
+}
+
+struct GetBytes;
+
+impl Request for GetBytes {
+    type Data = ();
+    type Response = Vec<u8>;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/bytes".into()
+    }
+
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(decoder::Bytes)
+    }
+}
+
+#[tokio::test]
+async fn text() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/text"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Hello, world!"))
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(GetText).await.unwrap();
+    assert_eq!(response, "Hello, world!");
+}
+
+#[tokio::test]
+async fn bytes() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/bytes"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0, 159, 146, 150]))
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(GetBytes).await.unwrap();
+    assert_eq!(response, vec![0, 159, 146, 150]);
+}
+
+#[cfg(feature = "xml")]
+#[tokio::test]
+async fn xml() {
+    use crate::utils::NameGreeting;
+
+    struct GetXml;
+
+    impl Request for GetXml {
+        type Data = ();
+        type Response = NameGreeting;
+
+        fn endpoint(&self) -> Path<'_> {
+            "/xml".into()
+        }
+
+        fn decoder(&self) -> Arc<dyn Decoder> {
+            Arc::new(decoder::Xml)
+        }
+    }
+
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/xml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<greeting><message>Hello, world!</message></greeting>"),
+        )
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(GetXml).await.unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hello, world!".into(),
+        }
+    );
+}
+
+#[cfg(feature = "cbor")]
+#[tokio::test]
+async fn cbor() {
+    use crate::utils::NameGreeting;
+
+    struct GetCbor;
+
+    impl Request for GetCbor {
+        type Data = ();
+        type Response = NameGreeting;
+
+        fn endpoint(&self) -> Path<'_> {
+            "/cbor".into()
+        }
+
+        fn decoder(&self) -> Arc<dyn Decoder> {
+            Arc::new(decoder::Cbor)
+        }
+    }
+
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    let greeting = NameGreeting {
+        message: "Hello, world!".into(),
+    };
+    let mut body = Vec::new();
+    ciborium::ser::into_writer(&greeting, &mut body).unwrap();
+    Mock::given(method("GET"))
+        .and(path("/cbor"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(GetCbor).await.unwrap();
+    assert_eq!(response, greeting);
+}
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn csv() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Row {
+        name: String,
+        age: u32,
+        email: Option<String>,
+    }
+
+    struct GetCsv(decoder::Csv);
+
+    impl Request for GetCsv {
+        type Data = ();
+        type Response = Vec<Row>;
+
+        fn endpoint(&self) -> Path<'_> {
+            "/csv".into()
+        }
+
+        fn decoder(&self) -> Arc<dyn Decoder> {
+            Arc::new(self.0)
+        }
+    }
+
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/csv"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("name;age;email\nJane;32;jane@example.com\nJohn;45;\n"),
+        )
+        .mount(&server)
+        .await;
+
+    let response = client
+        .oneshot(GetCsv(decoder::Csv::new().delimiter(b';')))
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        vec![
+            Row {
+                name: "Jane".into(),
+                age: 32,
+                email: Some("jane@example.com".into()),
+            },
+            Row {
+                name: "John".into(),
+                age: 45,
+                email: None,
+            },
+        ]
+    );
+}
+
+#[derive(Debug)]
+struct PrefixedJson;
+
+impl Decoder for PrefixedJson {
+    fn decode(&self, body: &[u8], output: Output<'_>) -> Result<(), Error> {
+        let body = body.strip_prefix(b")]}'").unwrap_or(body);
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        output.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+struct GetPrefixed;
+
+impl Request for GetPrefixed {
+    type Data = ();
+    type Response = Vec<u32>;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/prefixed".into()
+    }
+
+    fn decoder(&self) -> Arc<dyn Decoder> {
+        Arc::new(PrefixedJson)
+    }
+}
+
+#[tokio::test]
+async fn custom_decoder() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/prefixed"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(")]}'[1, 2, 3]"))
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(GetPrefixed).await.unwrap();
+    assert_eq!(response, vec![1, 2, 3]);
+}
@@ -1,8 +1,17 @@
 mod authorization;
+mod builder;
 mod data;
 mod default_headers;
+mod download;
 mod empty_response;
 mod errors;
+mod format;
+mod metadata;
+mod multipart;
 mod pagination;
+mod path;
 mod post;
+mod retry;
+mod timeout;
+mod url;
 mod utils;
@@ -0,0 +1,62 @@
+use crate::utils::{NameGreeting, QueryHello};
+use tower::ServiceExt;
+use tower_api_client::{Client, StatusCode, WithMeta};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(
+            ResponseTemplate::new(201)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(NameGreeting {
+                    message: "Hello, world!".into(),
+                }),
+        )
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn send_with_meta() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+    mount(&server).await;
+
+    let response = client
+        .send_with_meta(QueryHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.headers["etag"], "\"v1\"");
+    assert_eq!(response.body.message, "Hello, world!");
+}
+
+#[tokio::test]
+async fn with_meta_service() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+    mount(&server).await;
+
+    let response = client
+        .oneshot(WithMeta(QueryHello {
+            name: "world".into(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.headers["etag"], "\"v1\"");
+    assert_eq!(
+        response.into_body(),
+        NameGreeting {
+            message: "Hello, world!".into(),
+        }
+    );
+}
@@ -0,0 +1,86 @@
+use futures::stream;
+use tower::ServiceExt;
+use tower_api_client::multipart::{Form, Part};
+use tower_api_client::{Bytes, Client, EmptyResponse, Method, Path, Request, RequestData};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
+
+struct Upload {
+    streaming: bool,
+}
+
+impl Request for Upload {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/upload".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        let file = if self.streaming {
+            let chunks = vec![
+                Ok::<_, std::io::Error>(Bytes::from("file ")),
+                Ok("contents".into()),
+            ];
+            Part::stream(stream::iter(chunks))
+        } else {
+            Part::bytes("file contents")
+        };
+        let form = Form::new().text("name", "world").part(
+            "file",
+            file.file_name("hello.txt").content_type("text/plain"),
+        );
+        RequestData::Multipart(form)
+    }
+}
+
+fn expected_body(req: &MockRequest) -> String {
+    let content_type = req.headers.get(&"content-type".into()).unwrap().as_str();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+    format!(
+        "--{b}\r\n\
+        content-disposition: form-data; name=\"name\"\r\n\r\n\
+        world\r\n\
+        --{b}\r\n\
+        content-disposition: form-data; name=\"file\"; filename=\"hello.txt\"\r\n\
+        content-type: text/plain\r\n\r\n\
+        file contents\r\n\
+        --{b}--\r\n",
+        b = boundary
+    )
+}
+
+async fn mount(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(|req: &MockRequest| req.body == expected_body(req).as_bytes())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn multipart() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+    mount(&server).await;
+
+    client.oneshot(Upload { streaming: false }).await.unwrap();
+}
+
+#[tokio::test]
+async fn streaming_multipart() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+    mount(&server).await;
+
+    client.oneshot(Upload { streaming: true }).await.unwrap();
+}
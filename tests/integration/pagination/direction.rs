@@ -0,0 +1,127 @@
+use crate::utils::{items, mount_items, Items, ListItems};
+use futures::TryStreamExt;
+use serde_json::json;
+use tower_api_client::pagination::{
+    CursorPagination, Direction, LinkPagination, OffsetLimitPagination, PageNumberPagination,
+};
+use tower_api_client::{Client, ServiceExt};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn cursor_backward() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "c", json!({ "items": [3], "prev": "b" }), 1).await;
+    mount_items(&server, "b", json!({ "items": [2], "prev": "a" }), 1).await;
+    mount_items(&server, "a", json!({ "items": [1], "prev": null }), 1).await;
+
+    let request = CursorPagination::new(
+        ListItems::default(),
+        |_: &Items| None,
+        |request: &mut ListItems, cursor: &String| request.page = Some(cursor.clone()),
+    )
+    .prev_cursor(|items: &Items| items.prev.clone())
+    .cursor("c".to_string());
+    let pages = client
+        .paginate(request)
+        .direction(Direction::Backward)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![3, 2, 1]);
+}
+
+#[tokio::test]
+async fn page_number_backward() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    for page in 0..=2u32 {
+        mount_items(&server, &page.to_string(), json!({ "items": [page] }), 1).await;
+    }
+
+    let request = PageNumberPagination::new(ListItems::default(), |request, page| {
+        request.page = Some(page.to_string())
+    })
+    .first_page(2)
+    .lowest_page(0);
+    let pages = client
+        .paginate(request)
+        .direction(Direction::Backward)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![2, 1, 0]);
+}
+
+#[tokio::test]
+async fn offset_backward() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "3", json!({ "items": [3, 4] }), 1).await;
+    mount_items(&server, "1", json!({ "items": [1, 2] }), 1).await;
+    mount_items(&server, "0", json!({ "items": [0, 1] }), 1).await;
+
+    let request = OffsetLimitPagination::new(
+        ListItems::default(),
+        2,
+        |items: &Items| items.items.len(),
+        |request, offset, _limit| request.page = Some(offset.to_string()),
+    )
+    .offset(3);
+    let pages = client
+        .paginate(request)
+        .direction(Direction::Backward)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![3, 4, 1, 2, 0, 1]);
+}
+
+#[tokio::test]
+async fn link_backward() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", "3"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Link", r#"<items?page=2>; rel="prev""#)
+                .set_body_json(json!({ "items": [3] })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Link",
+                    r#"</items?page=3>; rel="next", </items?page=1>; rel="previous""#,
+                )
+                .set_body_json(json!({ "items": [2] })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_items(&server, "1", json!({ "items": [1] }), 1).await;
+
+    let request = LinkPagination::new(ListItems {
+        page: Some("3".into()),
+    });
+    let pages: Vec<_> = client
+        .paginate(request)
+        .direction(Direction::Backward)
+        .map_ok(|response| response.body)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![3, 2, 1]);
+}
@@ -0,0 +1,171 @@
+use crate::utils::{mount_items, mount_items_with, Items, ListItems};
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use tower_api_client::pagination::{CursorPagination, ErrorPolicy, PageNumberPagination, State};
+use tower_api_client::{Client, Error, ServiceExt};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn page_numbers() -> PageNumberPagination<ListItems> {
+    PageNumberPagination::new(ListItems { page: None }, |request, page| {
+        request.page = Some(page.to_string())
+    })
+    .last_page(|_: &Items| 4)
+}
+
+async fn mount(server: &MockServer, page: u32, status: u16, times: u64) {
+    let response =
+        ResponseTemplate::new(status).set_body_json(json!({ "items": [page], "next": null }));
+    mount_items_with(server, &page.to_string(), response, times).await;
+}
+
+async fn results<S>(mut pages: S) -> Vec<Result<u32, Error>>
+where
+    S: futures::Stream<Item = Result<Items, Error>> + Unpin,
+{
+    let mut results = Vec::new();
+    while let Some(page) = pages.next().await {
+        results.push(page.map(|page| page.items[0]));
+    }
+    results
+}
+
+fn pages(results: &[Result<u32, Error>]) -> Vec<Option<u32>> {
+    results
+        .iter()
+        .map(|page| page.as_ref().ok().copied())
+        .collect()
+}
+
+#[tokio::test]
+async fn stop_on_error() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount(&server, 1, 200, 1).await;
+    mount(&server, 2, 500, 1).await;
+
+    let mut stream = client.paginate(page_numbers());
+    let results = results(&mut stream).await;
+    assert_eq!(pages(&results), vec![Some(1), None]);
+    assert!(matches!(results[1], Err(Error::ServerError(_))));
+    // The failed page can be resumed from later
+    assert_eq!(stream.state(), State::Next(2));
+}
+
+#[tokio::test]
+async fn retry_failed_page() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount(&server, 1, 200, 1).await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .expect(2)
+        .mount(&server)
+        .await;
+    for page in 2..=4 {
+        mount(&server, page, 200, 1).await;
+    }
+
+    let stream = client
+        .paginate(page_numbers())
+        .on_error(ErrorPolicy::Retry {
+            max_retries: 2,
+            backoff: Duration::from_millis(10),
+        });
+    let results = results(stream).await;
+    assert_eq!(pages(&results), vec![Some(1), Some(2), Some(3), Some(4)]);
+}
+
+#[tokio::test]
+async fn retries_exhausted() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount(&server, 1, 200, 1).await;
+    mount(&server, 2, 500, 3).await;
+
+    let stream = client
+        .paginate(page_numbers())
+        .on_error(ErrorPolicy::Retry {
+            max_retries: 2,
+            backoff: Duration::from_millis(10),
+        });
+    let results = results(stream).await;
+    assert_eq!(pages(&results), vec![Some(1), None]);
+}
+
+#[tokio::test]
+async fn retry_while_prefetching() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", "3"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    for page in 1..=4 {
+        mount(&server, page, 200, 1).await;
+    }
+
+    let stream = client
+        .paginate(page_numbers())
+        .prefetch(3)
+        .on_error(ErrorPolicy::Retry {
+            max_retries: 1,
+            backoff: Duration::ZERO,
+        });
+    let results = results(stream).await;
+    assert_eq!(pages(&results), vec![Some(1), Some(2), Some(3), Some(4)]);
+}
+
+#[tokio::test]
+async fn skip_failed_page() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount(&server, 1, 200, 1).await;
+    mount(&server, 2, 200, 1).await;
+    mount(&server, 3, 500, 1).await;
+    mount(&server, 4, 200, 1).await;
+
+    let stream = client
+        .paginate(page_numbers())
+        .prefetch(2)
+        .on_error(ErrorPolicy::Skip);
+    let results = results(stream).await;
+    assert_eq!(pages(&results), vec![Some(1), Some(2), None, Some(4)]);
+}
+
+#[tokio::test]
+async fn repeated_cursor() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "a", json!({ "items": [1], "next": "a" }), 1).await;
+
+    let request = CursorPagination::new(
+        ListItems { page: None },
+        |items: &Items| items.next.clone(),
+        |request: &mut ListItems, cursor: &String| request.page = Some(cursor.clone()),
+    );
+    let mut stream = client
+        .paginate(request)
+        .detect_loops()
+        .resume_from(State::Next("a".to_string()));
+    let results = results(&mut stream).await;
+    assert_eq!(pages(&results), vec![Some(1), None]);
+    assert!(matches!(results[1], Err(Error::Pagination { .. })));
+    assert_eq!(stream.state(), State::End);
+}
@@ -0,0 +1,177 @@
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower_api_client::pagination::*;
+use tower_api_client::{Client, Path, Request, RequestData, ServiceExt};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Clone, Default, Serialize)]
+struct ListItems {
+    cursor: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    page: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Items {
+    items: Vec<u32>,
+    next: Option<String>,
+    total: Option<usize>,
+    pages: Option<usize>,
+}
+
+impl Request for ListItems {
+    type Data = Self;
+    type Response = Items;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/items".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Query(self)
+    }
+}
+
+async fn mount(server: &MockServer, param: (&str, &str), body: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param(param.0, param.1))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+async fn items<R>(client: Client, request: R) -> Vec<u32>
+where
+    R: PaginatedRequest<Response = Items> + 'static,
+    R::PaginationData: Clone + std::fmt::Debug,
+{
+    let pages: Vec<Items> = client.paginate(request).try_collect().await.unwrap();
+    pages.into_iter().flat_map(|page| page.items).collect()
+}
+
+#[tokio::test]
+async fn cursor_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(crate::utils::matchers::MissingQuery::new("cursor"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [1, 2],
+            "next": "abc",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount(
+        &server,
+        ("cursor", "abc"),
+        json!({"items": [3], "next": null}),
+    )
+    .await;
+
+    let request = CursorPagination::new(
+        ListItems::default(),
+        |items: &Items| items.next.clone(),
+        |request: &mut ListItems, cursor: &String| request.cursor = Some(cursor.clone()),
+    );
+    assert_eq!(items(client, request).await, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn offset_limit_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    mount(&server, ("offset", "0"), json!({"items": [1, 2]})).await;
+    mount(&server, ("offset", "2"), json!({"items": [3, 4]})).await;
+    // A short page is the last one
+    mount(&server, ("offset", "4"), json!({"items": [5]})).await;
+
+    let request = OffsetLimitPagination::new(
+        ListItems::default(),
+        2,
+        |items: &Items| items.items.len(),
+        |request: &mut ListItems, offset, limit| {
+            request.offset = Some(offset);
+            request.limit = Some(limit);
+        },
+    );
+    assert_eq!(items(client, request).await, vec![1, 2, 3, 4, 5]);
+}
+
+#[tokio::test]
+async fn offset_limit_pagination_with_total() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    mount(
+        &server,
+        ("offset", "10"),
+        json!({"items": [1, 2], "total": 14}),
+    )
+    .await;
+    mount(
+        &server,
+        ("offset", "12"),
+        json!({"items": [3, 4], "total": 14}),
+    )
+    .await;
+
+    let request = OffsetLimitPagination::new(
+        ListItems::default(),
+        2,
+        |items: &Items| items.items.len(),
+        |request: &mut ListItems, offset, limit| {
+            request.offset = Some(offset);
+            request.limit = Some(limit);
+        },
+    )
+    .offset(10)
+    .total(|items: &Items| items.total.unwrap());
+    assert_eq!(items(client, request).await, vec![1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn page_number_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    mount(&server, ("page", "1"), json!({"items": [1], "pages": 3})).await;
+    mount(&server, ("page", "2"), json!({"items": [2], "pages": 3})).await;
+    mount(&server, ("page", "3"), json!({"items": [3], "pages": 3})).await;
+
+    let request = PageNumberPagination::new(ListItems::default(), |request, page| {
+        request.page = Some(page)
+    })
+    .last_page(|items: &Items| items.pages.unwrap());
+    assert_eq!(items(client, request).await, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn page_number_pagination_until_empty() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    mount(&server, ("page", "0"), json!({"items": [1, 2]})).await;
+    mount(&server, ("page", "1"), json!({"items": [3]})).await;
+    mount(&server, ("page", "2"), json!({"items": []})).await;
+
+    let request = PageNumberPagination::new(ListItems::default(), |request, page| {
+        request.page = Some(page)
+    })
+    .first_page(0)
+    .page_len(|items: &Items| items.items.len());
+    assert_eq!(items(client, request).await, vec![1, 2, 3]);
+}
@@ -0,0 +1,61 @@
+use crate::utils::{item_pages, mount_items, mount_items_with, Items, ListItems};
+use futures::StreamExt;
+use serde_json::json;
+use tower_api_client::pagination::PageNumberPagination;
+use tower_api_client::{Client, ServiceExt};
+use wiremock::{MockServer, ResponseTemplate};
+
+fn request() -> PageNumberPagination<ListItems> {
+    item_pages().page_len(|items: &Items| items.items.len())
+}
+
+#[tokio::test]
+async fn paginate_items() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "1", json!({ "items": [1, 2] }), 1).await;
+    // Empty pages in between are skipped
+    mount_items(&server, "2", json!({ "items": [3] }), 1).await;
+    mount_items(&server, "3", json!({ "items": [] }), 1).await;
+
+    let mut items = client.paginate_items(request(), |page| page.items);
+    assert_eq!(items.next().await.unwrap().unwrap(), 1);
+    assert_eq!(items.next().await.unwrap().unwrap(), 2);
+    assert_eq!(items.next().await.unwrap().unwrap(), 3);
+    assert!(items.next().await.is_none());
+}
+
+#[tokio::test]
+async fn max_items() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "1", json!({ "items": [1, 2] }), 1).await;
+    mount_items(&server, "2", json!({ "items": [3, 4] }), 1).await;
+    // The limit is reached on the second page, so the third is never requested
+    mount_items(&server, "3", json!({ "items": [5, 6] }), 0).await;
+
+    let items = client
+        .paginate_items(request(), |page| page.items)
+        .max_items(3)
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(items, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn collect_all_error() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount_items(&server, "1", json!({ "items": [1, 2] }), 1).await;
+    mount_items_with(&server, "2", ResponseTemplate::new(500), 1).await;
+
+    let result = client
+        .paginate_items(request(), |page| page.items)
+        .collect_all()
+        .await;
+    assert!(result.is_err());
+}
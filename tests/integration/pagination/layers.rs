@@ -0,0 +1,91 @@
+use crate::utils::{all_item_pages, items, mount_items_with, Items};
+use futures::TryStreamExt;
+use serde_json::json;
+use std::time::Duration;
+use tower::timeout::error::Elapsed;
+use tower::{BoxError, ServiceBuilder};
+use tower_api_client::{Client, ServiceExt};
+use wiremock::{MockServer, ResponseTemplate};
+
+async fn setup(delay: Duration) -> (MockServer, Client) {
+    let server = MockServer::start().await;
+    for page in 1..=3u32 {
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "items": [page], "pages": 3 }))
+            .set_delay(delay);
+        mount_items_with(&server, &page.to_string(), response, 0..).await;
+    }
+    let client = Client::new(server.uri());
+    (server, client)
+}
+
+#[tokio::test]
+async fn paginate_through_timeout() {
+    let _ = env_logger::try_init();
+    let (_server, client) = setup(Duration::ZERO).await;
+
+    let svc = ServiceBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .service(client);
+    let pages: Vec<Items> = svc.paginate(all_item_pages()).try_collect().await.unwrap();
+    assert_eq!(items(pages), vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn prefetch_through_timeout() {
+    let _ = env_logger::try_init();
+    let (_server, client) = setup(Duration::ZERO).await;
+
+    let svc = ServiceBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .service(client);
+    let pages: Vec<Items> = svc
+        .paginate(all_item_pages())
+        .prefetch(3)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn timeout_elapsed() {
+    let _ = env_logger::try_init();
+    let (_server, client) = setup(Duration::from_millis(500)).await;
+
+    let svc = ServiceBuilder::new()
+        .timeout(Duration::from_millis(50))
+        .service(client);
+    let result: Result<Vec<Items>, BoxError> = svc.paginate(all_item_pages()).try_collect().await;
+    assert!(result.unwrap_err().is::<Elapsed>());
+}
+
+#[tokio::test]
+async fn paginate_through_rate_limit() {
+    let _ = env_logger::try_init();
+    let (_server, client) = setup(Duration::ZERO).await;
+
+    let svc = ServiceBuilder::new()
+        .rate_limit(1, Duration::from_millis(50))
+        .service(client);
+    let pages: Vec<Items> = svc.paginate(all_item_pages()).try_collect().await.unwrap();
+    assert_eq!(items(pages), vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn paginate_through_buffer() {
+    let _ = env_logger::try_init();
+    let (_server, client) = setup(Duration::ZERO).await;
+
+    let svc = ServiceBuilder::new()
+        .buffer(4)
+        .timeout(Duration::from_secs(5))
+        .service(client);
+    let pages: Vec<Items> = svc
+        .paginate(all_item_pages())
+        .prefetch(2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(items(pages), vec![1, 2, 3]);
+}
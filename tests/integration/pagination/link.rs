@@ -0,0 +1,156 @@
+use futures::TryStreamExt;
+use serde::Serialize;
+use tower_api_client::pagination::{find_link, LinkPagination};
+use tower_api_client::{header::HeaderMap, Client, Path, Request, RequestData, ServiceExt};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
+
+#[derive(Clone, Serialize)]
+struct ListRepos {
+    per_page: usize,
+}
+
+impl Request for ListRepos {
+    type Data = Self;
+    type Response = Vec<String>;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/repos".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Query(self)
+    }
+}
+
+fn query(query: &'static str) -> impl Fn(&MockRequest) -> bool {
+    move |req: &MockRequest| req.url.query() == Some(query)
+}
+
+#[tokio::test]
+async fn link_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(format!("{}/api", uri));
+
+    // An absolute link
+    Mock::given(method("GET"))
+        .and(path("/api/repos"))
+        .and(query("per_page=2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Link",
+                    format!(
+                        r#"<{0}/api/repos?per_page=2&page=2>; rel="next", <{0}/api/repos?per_page=2&page=3>; rel="last""#,
+                        uri
+                    )
+                    .as_str(),
+                )
+                .set_body_json(vec!["a", "b"]),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A link relative to the host
+    Mock::given(method("GET"))
+        .and(path("/api/repos"))
+        .and(query("per_page=2&page=2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Link", r#"</api/repos?per_page=2&page=3>; rel=next"#)
+                .set_body_json(vec!["c", "d"]),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A link relative to the request path, and no next link on the last page
+    Mock::given(method("GET"))
+        .and(path("/api/repos"))
+        .and(query("per_page=2&page=3"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Link", r#"<repos?per_page=2&page=1>; rel="first prev""#)
+                .set_body_json(vec!["e"]),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client
+        .paginate(LinkPagination::new(ListRepos { per_page: 2 }))
+        .map_ok(|response| response.body)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+}
+
+#[tokio::test]
+async fn cross_origin_link() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let other = MockServer::start().await;
+    let client = Client::builder(server.uri())
+        .bearer_auth("token")
+        .header("X-Api-Key", "secret")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/repos"))
+        .and(header("authorization", "Bearer token"))
+        .and(header("x-api-key", "secret"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Link",
+                    format!(r#"<{}/repos?page=2>; rel="next""#, other.uri()).as_str(),
+                )
+                .set_body_json(vec!["a"]),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The credentials of the client aren't sent to another origin
+    Mock::given(method("GET"))
+        .and(path("/repos"))
+        .and(query("page=2"))
+        .and(|req: &MockRequest| {
+            req.headers.keys().all(|name| {
+                !name.as_str().eq_ignore_ascii_case("authorization")
+                    && !name.as_str().eq_ignore_ascii_case("x-api-key")
+            })
+        })
+        .respond_with(ResponseTemplate::new(200).set_body_json(vec!["b"]))
+        .expect(1)
+        .mount(&other)
+        .await;
+
+    let pages: Vec<_> = client
+        .paginate(LinkPagination::new(ListRepos { per_page: 1 }))
+        .map_ok(|response| response.body)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![vec!["a"], vec!["b"]]);
+}
+
+#[test]
+fn parse_link_header() {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        "link",
+        r#"<https://example.com/a,b>; title="x, y"; rel="prev", <https://example.com/c>;rel="next last""#
+            .parse()
+            .unwrap(),
+    );
+    assert_eq!(find_link(&headers, "prev"), Some("https://example.com/a,b"));
+    assert_eq!(find_link(&headers, "NEXT"), Some("https://example.com/c"));
+    assert_eq!(find_link(&headers, "last"), Some("https://example.com/c"));
+    assert_eq!(find_link(&headers, "first"), None);
+}
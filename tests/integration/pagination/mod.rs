@@ -1,2 +1,10 @@
+mod direction;
+mod errors;
+mod helpers;
+mod items;
+mod layers;
+mod link;
 mod path;
+mod prefetch;
 mod query;
+mod resume;
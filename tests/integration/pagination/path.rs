@@ -1,8 +1,7 @@
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
 use tower_api_client::pagination::*;
-use tower_api_client::{Client, Request, ServiceExt};
+use tower_api_client::{Client, Path, Request, ServiceExt};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
@@ -21,7 +20,7 @@ impl Request for PaginationRequest {
     type Data = ();
     type Response = PaginationResponse;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         match self.page {
             Some(page) => format!("/nested/page/{}", page).into(),
             None => "/nested/page".into(),
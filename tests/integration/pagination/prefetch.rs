@@ -0,0 +1,109 @@
+use crate::utils::{all_item_pages, mount_items_with, Items};
+use futures::TryStreamExt;
+use serde_json::json;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceBuilder;
+use tower_api_client::{Client, ServiceExt};
+use wiremock::{MockServer, ResponseTemplate};
+
+async fn mount(server: &MockServer, delay: Duration) {
+    for page in 1..=5u32 {
+        // Later pages respond faster, to check that pages are yielded in order
+        let delay = if page == 1 {
+            Duration::ZERO
+        } else {
+            delay * (7 - page) / 5
+        };
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "items": [page], "pages": 5 }))
+            .set_delay(delay);
+        mount_items_with(server, &page.to_string(), response, 1).await;
+    }
+}
+
+/// Counts the requests in flight through a service, recording the most there were at once.
+#[derive(Clone, Default)]
+struct InFlight {
+    current: Arc<AtomicUsize>,
+    max: Arc<AtomicUsize>,
+}
+
+impl InFlight {
+    fn track<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(current, Ordering::SeqCst);
+        let this = self.clone();
+        async move {
+            let output = future.await;
+            this.current.fetch_sub(1, Ordering::SeqCst);
+            output
+        }
+    }
+
+    fn max(&self) -> usize {
+        self.max.load(Ordering::SeqCst)
+    }
+}
+
+#[tokio::test]
+async fn prefetch() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    mount(&server, Duration::from_millis(50)).await;
+
+    let in_flight = InFlight::default();
+    let tracked = in_flight.clone();
+    let items = ServiceBuilder::new()
+        .map_future(move |future| tracked.track(future))
+        .service(client.clone())
+        .paginate_items(all_item_pages(), |page| page.items)
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    assert_eq!(in_flight.max(), 1);
+
+    server.reset().await;
+    mount(&server, Duration::from_millis(50)).await;
+    let in_flight = InFlight::default();
+    let tracked = in_flight.clone();
+    let pages: Vec<Items> = ServiceBuilder::new()
+        .map_future(move |future| tracked.track(future))
+        .service(client)
+        .paginate(all_item_pages())
+        .prefetch(4)
+        .try_collect()
+        .await
+        .unwrap();
+    let items: Vec<u32> = pages.into_iter().flat_map(|page| page.items).collect();
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    // The first page is fetched on its own, and the remaining pages concurrently
+    assert_eq!(in_flight.max(), 4);
+}
+
+#[tokio::test]
+async fn prefetch_with_backpressure() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    mount(&server, Duration::from_millis(50)).await;
+    let in_flight = InFlight::default();
+    let tracked = in_flight.clone();
+    let client = ServiceBuilder::new()
+        .concurrency_limit(2)
+        .map_future(move |future| tracked.track(future))
+        .service(Client::new(server.uri()));
+
+    let pages: Vec<Items> = client
+        .paginate(all_item_pages())
+        .prefetch(4)
+        .try_collect()
+        .await
+        .unwrap();
+    let items: Vec<u32> = pages.into_iter().flat_map(|page| page.items).collect();
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    assert_eq!(in_flight.max(), 2);
+}
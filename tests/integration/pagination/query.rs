@@ -1,9 +1,8 @@
 use crate::utils::matchers::MissingQuery;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
 use tower_api_client::pagination::*;
-use tower_api_client::{Client, Request, RequestData, ServiceExt};
+use tower_api_client::{Client, Path, Request, RequestData, ServiceExt};
 use wiremock::matchers::{method, path, query_param};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
@@ -22,7 +21,7 @@ impl Request for PaginationRequest {
     type Data = Self;
     type Response = PaginationResponse;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/page".into()
     }
 
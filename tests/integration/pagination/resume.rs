@@ -0,0 +1,57 @@
+use crate::utils::matchers::MissingQuery;
+use crate::utils::{mount_items, Items, ListItems};
+use futures::{StreamExt, TryStreamExt};
+use serde_json::json;
+use tower_api_client::pagination::{CursorPagination, State};
+use tower_api_client::{Client, ServiceExt};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request() -> CursorPagination<ListItems> {
+    CursorPagination::new(
+        ListItems::default(),
+        |items: &Items| items.next.clone(),
+        |request: &mut ListItems, cursor: &String| request.page = Some(cursor.clone()),
+    )
+}
+
+#[tokio::test]
+async fn resume_from_checkpoint() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(MissingQuery::new("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [1],
+            "next": "b",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_items(&server, "b", json!({ "items": [2], "next": "c" }), 1).await;
+    mount_items(&server, "c", json!({ "items": [3], "next": null }), 1).await;
+
+    let mut pages = client.clone().paginate(request());
+    assert_eq!(pages.state(), State::Start(None));
+    let page = pages.next().await.unwrap().unwrap();
+    assert_eq!(page.items, vec![1]);
+
+    // Checkpoint after the first page, and continue with a new stream
+    let checkpoint = serde_json::to_string(&pages.state()).unwrap();
+    drop(pages);
+    let state: State<String> = serde_json::from_str(&checkpoint).unwrap();
+    assert_eq!(state, State::Next("b".into()));
+
+    let mut pages = client.paginate(request()).resume_from(state);
+    let items: Vec<u32> = pages
+        .by_ref()
+        .map_ok(|page| page.items)
+        .try_concat()
+        .await
+        .unwrap();
+    assert_eq!(items, vec![2, 3]);
+    assert_eq!(pages.state(), State::End);
+}
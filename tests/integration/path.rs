@@ -0,0 +1,109 @@
+use serde_json::json;
+use tower::ServiceExt;
+use tower_api_client::{Client, Error, Path, Request};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct GetFile {
+    owner: String,
+    name: String,
+}
+
+impl Request for GetFile {
+    type Data = ();
+    type Response = serde_json::Value;
+
+    fn endpoint(&self) -> Path<'_> {
+        Path::new("/users/{owner}/files/{name}")
+            .param("owner", &self.owner)
+            .param("name", &self.name)
+    }
+}
+
+struct Unfilled;
+
+impl Request for Unfilled {
+    type Data = ();
+    type Response = serde_json::Value;
+
+    fn endpoint(&self) -> Path<'_> {
+        Path::new("/users/{id}")
+    }
+}
+
+#[test]
+fn render_path() {
+    let path = Path::new("/items/{id}").param("id", 42);
+    assert_eq!(path.to_string(), "/items/42");
+
+    let path = Path::new("/items/{id}/{id}").param("id", "a?b#c");
+    assert_eq!(path.to_string(), "/items/a%3Fb%23c/a%3Fb%23c");
+
+    let path = Path::new("/items/{id}/{missing}").param("id", "100%");
+    assert_eq!(path.to_string(), "/items/100%25/{missing}");
+
+    // Encoded dots are a literal segment rather than a dot segment
+    let path = Path::new("/items/{id}").param("id", "%2E%2E");
+    assert_eq!(path.to_string(), "/items/%252E%252E");
+}
+
+#[tokio::test]
+async fn percent_encoded_params() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .and(path("/users/j%C3%BCrgen/files/notes%20%2F%202023.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .oneshot(GetFile {
+            owner: "jürgen".into(),
+            name: "notes / 2023.txt".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response, json!({ "ok": true }));
+}
+
+#[tokio::test]
+async fn dot_segments() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(format!("{}/api", server.uri()));
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    for name in [".", ".."] {
+        let error = client
+            .clone()
+            .oneshot(GetFile {
+                owner: "jane".into(),
+                name: name.into(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidEndpoint(_)), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn unfilled_placeholder() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let error = client.oneshot(Unfilled).await.unwrap_err();
+    assert!(matches!(error, Error::InvalidEndpoint(_)));
+}
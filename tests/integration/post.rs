@@ -1,8 +1,7 @@
 use serde::Serialize;
 use serde_json::json;
-use std::borrow::Cow;
 use tower::ServiceExt;
-use tower_api_client::{Client, EmptyResponse, Method, Request, RequestData};
+use tower_api_client::{Client, EmptyResponse, Method, Path, Request, RequestData};
 use wiremock::matchers::{body_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -16,7 +15,7 @@ impl Request for CreateUser {
     type Response = EmptyResponse;
     const METHOD: Method = Method::POST;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/user".into()
     }
 
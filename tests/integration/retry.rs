@@ -0,0 +1,160 @@
+use crate::utils::NameGreeting;
+use serde::Serialize;
+use std::time::Duration;
+use tower::{ServiceBuilder, ServiceExt};
+use tower_api_client::retry::RetryLayer;
+use tower_api_client::{
+    Client, EmptyResponse, Error, Method, Path, Request, RequestData, StatusCode,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Clone)]
+struct Hello;
+
+impl Request for Hello {
+    type Data = ();
+    type Response = NameGreeting;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct CreateHello;
+
+impl Request for CreateHello {
+    type Data = Self;
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Json(self)
+    }
+}
+
+fn layer() -> RetryLayer {
+    RetryLayer::new().backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = ServiceBuilder::new()
+        .layer(layer())
+        .service(Client::new(&uri));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(NameGreeting {
+            message: "Hello, world!".into(),
+        }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.oneshot(Hello).await.unwrap();
+    assert_eq!(response.message, "Hello, world!");
+}
+
+#[tokio::test]
+async fn honors_retry_after() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = ServiceBuilder::new()
+        .layer(layer().max_attempts(2))
+        .service(Client::new(&uri));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(NameGreeting {
+            message: "Hello, world!".into(),
+        }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(Hello).await.unwrap();
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = ServiceBuilder::new()
+        .layer(layer())
+        .service(Client::new(&uri));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let err = client.oneshot(Hello).await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::INTERNAL_SERVER_ERROR));
+}
+
+#[tokio::test]
+async fn does_not_retry_non_idempotent_requests() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = ServiceBuilder::new()
+        .layer(layer())
+        .service(Client::new(&uri));
+
+    Mock::given(method("POST"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.oneshot(CreateHello).await.unwrap_err();
+}
+
+#[tokio::test]
+async fn custom_classifier() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = ServiceBuilder::new()
+        .layer(layer().retry_if(|err: &Error| err.status() == Some(StatusCode::NOT_FOUND)))
+        .service(Client::new(&uri));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    client.oneshot(Hello).await.unwrap_err();
+}
@@ -0,0 +1,113 @@
+use crate::utils::EmptyHello;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tower::ServiceExt;
+use tower_api_client::{Client, EmptyResponse, Error, Path, Request, TimeoutPhase};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct PatientHello;
+
+impl Request for PatientHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello".into()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+}
+
+async fn mount(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn client_timeout() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    mount(&server).await;
+
+    assert!(matches!(
+        client.oneshot(EmptyHello).await.unwrap_err(),
+        Error::Timeout(TimeoutPhase::Request)
+    ));
+}
+
+#[tokio::test]
+async fn request_timeout_overrides_client_timeout() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    mount(&server).await;
+
+    client.oneshot(PatientHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_timeout() {
+    let _ = env_logger::try_init();
+    // A non-routable address, to which connection attempts are left unanswered. Some sandboxed
+    // networks answer every connection, in which case the timeout can't be observed.
+    let addr = "10.255.255.1:80";
+    if tokio::time::timeout(Duration::from_millis(200), TcpStream::connect(addr))
+        .await
+        .is_ok()
+    {
+        eprintln!("{} is reachable, skipping connect_timeout", addr);
+        return;
+    }
+
+    let client = Client::builder(format!("http://{}", addr))
+        .connect_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.oneshot(EmptyHello).await.unwrap_err(),
+        Error::Timeout(TimeoutPhase::Connect)
+    ));
+}
+
+#[tokio::test]
+async fn read_timeout() {
+    let _ = env_logger::try_init();
+    // A server which sends the headers and part of the body, and then stalls
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0; 1024];
+        let _ = stream.read(&mut buf).await.unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 16\r\n\r\n{\"message\":")
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+
+    let client = Client::builder(format!("http://{}", addr))
+        .read_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        client.oneshot(EmptyHello).await.unwrap_err(),
+        Error::Timeout(TimeoutPhase::Body)
+    ));
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+use tower::ServiceExt;
+use tower_api_client::{Client, EmptyResponse, Path, Request, RequestData};
+use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
+
+#[derive(Serialize)]
+struct Get {
+    #[serde(skip)]
+    endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u32>,
+}
+
+impl Get {
+    fn new<S: Into<String>>(endpoint: S) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            page: None,
+        }
+    }
+}
+
+impl Request for Get {
+    type Data = Self;
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Path<'_> {
+        self.endpoint.as_str().into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        match self.page {
+            Some(_) => RequestData::Query(self),
+            None => RequestData::Empty,
+        }
+    }
+}
+
+/// Match the path and query of the request exactly.
+fn url(expected: &'static str) -> impl Fn(&MockRequest) -> bool {
+    move |req: &MockRequest| match req.url.query() {
+        Some(query) => format!("{}?{}", req.url.path(), query) == expected,
+        None => req.url.path() == expected,
+    }
+}
+
+async fn mount(server: &MockServer, expected: &'static str) {
+    Mock::given(url(expected))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn base_path() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    mount(&server, "/api/v2/users").await;
+    mount(&server, "/api/v2/users/").await;
+    mount(&server, "/api/v2/").await;
+
+    let client = Client::new(format!("{}/api/v2", server.uri()));
+    client.clone().oneshot(Get::new("/users")).await.unwrap();
+    let client = Client::new(format!("{}/api/v2/", server.uri()));
+    client.clone().oneshot(Get::new("users/")).await.unwrap();
+    client.oneshot(Get::new("")).await.unwrap();
+}
+
+#[tokio::test]
+async fn merge_query() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    mount(&server, "/api/items?version=2&sort=asc&page=3&key=k").await;
+
+    let client = Client::builder(format!("{}/api?version=2#docs", server.uri()))
+        .query_auth(vec![("key", "k")])
+        .build()
+        .unwrap();
+    let mut request = Get::new("/items?sort=asc#top");
+    request.page = Some(3);
+    client.oneshot(request).await.unwrap();
+}
+
+#[tokio::test]
+async fn absolute_endpoint() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let other = MockServer::start().await;
+    mount(&server, "/next?version=2&page=2").await;
+    mount(&other, "/elsewhere?page=2").await;
+
+    let client = Client::new(format!("{}/api?version=2", server.uri()));
+    client
+        .clone()
+        .oneshot(Get::new(format!("{}/next?page=2", server.uri())))
+        .await
+        .unwrap();
+    client
+        .oneshot(Get::new(format!("{}/elsewhere?page=2", other.uri())))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn literal_endpoint() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    mount(&server, "/api/v2/files/%7Bname%7D").await;
+
+    // Endpoints converted from strings aren't checked like templates
+    let client = Client::new(format!("{}/api/v2", server.uri()));
+    client.oneshot(Get::new("/files/{name}")).await.unwrap();
+}
@@ -1,6 +1,9 @@
+use futures::stream;
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
-use tower_api_client::{EmptyResponse, Request, RequestData};
+use tower_api_client::pagination::PageNumberPagination;
+use tower_api_client::{Bytes, EmptyResponse, Method, Path, Query, Request, RequestData};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate, Times};
 
 pub mod matchers;
 
@@ -10,7 +13,7 @@ impl Request for EmptyHello {
     type Data = ();
     type Response = EmptyResponse;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/hello".into()
     }
 }
@@ -30,6 +33,18 @@ pub struct FormHello {
     pub name: String,
 }
 
+#[derive(Serialize)]
+pub struct HelloOptions {
+    pub lang: String,
+}
+
+#[derive(Serialize)]
+pub struct QueryJsonHello {
+    #[serde(skip)]
+    pub options: HelloOptions,
+    pub name: String,
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct NameGreeting {
     pub message: String,
@@ -39,7 +54,7 @@ impl Request for QueryHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/hello".into()
     }
 
@@ -52,7 +67,7 @@ impl Request for JsonHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/hello".into()
     }
 
@@ -65,7 +80,7 @@ impl Request for FormHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<'_, str> {
+    fn endpoint(&self) -> Path<'_> {
         "/hello".into()
     }
 
@@ -73,3 +88,133 @@ impl Request for FormHello {
         RequestData::Form(self)
     }
 }
+
+impl Request for QueryJsonHello {
+    type Data = Self;
+    type Response = NameGreeting;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/hello?version=1".into()
+    }
+
+    fn query(&self) -> Option<Query<'_>> {
+        Some(Query::new(&self.options))
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Json(self)
+    }
+}
+
+/// A request uploading newline-delimited JSON to `/upload`, either as a stream or all at once.
+pub struct Upload {
+    chunks: Vec<&'static str>,
+    stream: bool,
+}
+
+impl Request for Upload {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/upload".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        if self.stream {
+            let chunks = self
+                .chunks
+                .clone()
+                .into_iter()
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk.as_bytes())));
+            RequestData::stream("application/x-ndjson", stream::iter(chunks))
+        } else {
+            RequestData::raw("application/x-ndjson", self.chunks.concat())
+        }
+    }
+}
+
+pub fn upload(stream: bool) -> Upload {
+    Upload {
+        chunks: vec!["{\"id\":1}\n", "{\"id\":2}\n"],
+        stream,
+    }
+}
+
+/// A paginated request for the `/items` endpoint, sending the page as a query parameter.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ListItems {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+}
+
+/// A page of items, along with the pagination data used by the different pagination schemes.
+#[derive(Debug, Deserialize)]
+pub struct Items {
+    pub items: Vec<u32>,
+    #[serde(default)]
+    pub next: Option<String>,
+    #[serde(default)]
+    pub prev: Option<String>,
+    #[serde(default)]
+    pub pages: usize,
+}
+
+impl Request for ListItems {
+    type Data = Self;
+    type Response = Items;
+
+    fn endpoint(&self) -> Path<'_> {
+        "/items".into()
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Query(self)
+    }
+}
+
+/// Paginate `ListItems` by page number, starting from the first page.
+pub fn item_pages() -> PageNumberPagination<ListItems> {
+    PageNumberPagination::new(ListItems::default(), |request, page| {
+        request.page = Some(page.to_string())
+    })
+}
+
+/// Paginate `ListItems` by page number, up to the number of pages returned with each page.
+pub fn all_item_pages() -> PageNumberPagination<ListItems> {
+    item_pages().last_page(|items: &Items| items.pages)
+}
+
+/// Flatten the items of the pages, in the order in which the pages were yielded.
+pub fn items(pages: Vec<Items>) -> Vec<u32> {
+    pages.into_iter().flat_map(|page| page.items).collect()
+}
+
+/// Respond to the request for a page of `ListItems` with `body`.
+pub async fn mount_items<T>(server: &MockServer, page: &str, body: serde_json::Value, expected: T)
+where
+    T: Into<Times>,
+{
+    let response = ResponseTemplate::new(200).set_body_json(body);
+    mount_items_with(server, page, response, expected).await
+}
+
+/// Respond to the request for a page of `ListItems` with `response`.
+pub async fn mount_items_with<T>(
+    server: &MockServer,
+    page: &str,
+    response: ResponseTemplate,
+    expected: T,
+) where
+    T: Into<Times>,
+{
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", page))
+        .respond_with(response)
+        .expect(expected)
+        .mount(server)
+        .await;
+}
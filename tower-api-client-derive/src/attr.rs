@@ -0,0 +1,109 @@
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{Attribute, Ident, LitStr, Type};
+
+/// The format of the body sent with the request.
+#[derive(Clone, Copy)]
+pub(crate) enum BodyFormat {
+    Json,
+    Form,
+}
+
+/// The attributes set on the struct deriving `Request`.
+#[derive(Default)]
+pub(crate) struct RequestAttrs {
+    pub(crate) method: Option<Ident>,
+    pub(crate) path: Option<LitStr>,
+    pub(crate) response: Option<Type>,
+    pub(crate) error: Option<Type>,
+    pub(crate) body: Option<(BodyFormat, Span)>,
+    pub(crate) query: Option<Span>,
+}
+
+impl RequestAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("request")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("method") {
+                    out.method = Some(parse_method(&meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("path") {
+                    out.path = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("response") {
+                    out.response = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    out.error = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("body") {
+                    let format: Ident = meta.value()?.parse()?;
+                    let format = match format.to_string().as_str() {
+                        "json" => BodyFormat::Json,
+                        "form" => BodyFormat::Form,
+                        _ => {
+                            return Err(syn::Error::new(format.span(), "expected `json` or `form`"))
+                        }
+                    };
+                    out.body = Some((format, meta.path.span()));
+                } else if meta.path.is_ident("query") {
+                    out.query = Some(meta.path.span());
+                } else {
+                    return Err(meta.error("unsupported request attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+fn parse_method(method: &LitStr) -> syn::Result<Ident> {
+    let value = method.value().to_ascii_uppercase();
+    match value.as_str() {
+        "GET" | "POST" | "PUT" | "DELETE" | "PATCH" | "HEAD" | "OPTIONS" | "TRACE" | "CONNECT" => {
+            Ok(Ident::new(&value, method.span()))
+        }
+        _ => Err(syn::Error::new(method.span(), "unsupported HTTP method")),
+    }
+}
+
+/// How a field is sent with the request.
+pub(crate) enum FieldKind {
+    Query,
+    Body,
+    Header(LitStr),
+}
+
+impl FieldKind {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
+        let mut kind = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("request")) {
+            attr.parse_nested_meta(|meta| {
+                let parsed = if meta.path.is_ident("query") {
+                    FieldKind::Query
+                } else if meta.path.is_ident("body") {
+                    FieldKind::Body
+                } else if meta.path.is_ident("header") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    if !is_header_name(&name.value()) {
+                        return Err(syn::Error::new(name.span(), "invalid header name"));
+                    }
+                    FieldKind::Header(name)
+                } else {
+                    return Err(meta.error("unsupported request attribute"));
+                };
+                if kind.is_some() {
+                    return Err(meta.error("a field can only be sent in one way"));
+                }
+                kind = Some(parsed);
+                Ok(())
+            })?;
+        }
+        Ok(kind)
+    }
+}
+
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
@@ -0,0 +1,285 @@
+//! Derive macro for the `Request` trait of
+//! [tower-api-client](https://docs.rs/tower-api-client). The macro is re-exported by
+//! tower-api-client with the `derive` feature enabled.
+use attr::{BodyFormat, FieldKind, RequestAttrs};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, LitStr, Member, Type};
+
+mod attr;
+
+/// Derive the `Request` trait from attributes.
+///
+/// The struct is annotated with `#[request(...)]`, taking:
+///
+/// - `path = "/users/{id}"`: the endpoint of the request, required. Fields named in braces are
+///   interpolated into the path using their `Display` implementation, and percent-encoded as
+///   path segments.
+/// - `method = "POST"`: the HTTP method. Defaults to `GET`.
+/// - `response = User`: the `Response` type. Defaults to `EmptyResponse`.
+/// - `error = ApiError`: also implement `TypedErrorRequest`, with this type as the `ErrorBody`.
+/// - `body = json` or `body = form`: send the struct itself as the body of the request, or the
+///   format of the field marked as body.
+/// - `query`: send the struct itself as query parameters.
+///
+/// When the struct itself is sent, it must implement `Serialize`, and fields only used in the path
+/// or headers should be skipped with `#[serde(skip)]`.
+///
+/// Fields can be annotated with:
+///
+/// - `#[request(query)]`: send the field as the query parameters of the request, which can be
+///   combined with a body.
+/// - `#[request(body)]`: send the field as the body of the request, as JSON unless another
+///   format is set on the struct.
+/// - `#[request(header = "X-Request-Id")]`: send the field as a header using its `Display`
+///   implementation. Optional fields are only sent when set. Values which aren't valid header
+///   values make the request fail when it is sent.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use tower_api_client::Request;
+///
+/// #[derive(Deserialize)]
+/// struct Post {
+///     id: u64,
+///     title: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct NewPost {
+///     title: String,
+/// }
+///
+/// #[derive(Request)]
+/// #[request(method = "POST", path = "/users/{user_id}/posts", response = Post)]
+/// struct CreatePost {
+///     user_id: u64,
+///     #[request(header = "Idempotency-Key")]
+///     idempotency_key: Option<String>,
+///     #[request(body)]
+///     post: NewPost,
+/// }
+/// ```
+#[proc_macro_derive(Request, attributes(request))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = RequestAttrs::parse(&input.attrs)?;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Request can only be derived for structs",
+            ))
+        }
+    };
+    let path = attrs.path.as_ref().ok_or_else(|| {
+        syn::Error::new(
+            input.ident.span(),
+            "missing request path, e.g. #[request(path = \"/users\")]",
+        )
+    })?;
+
+    let mut query = None;
+    let mut body = None;
+    let mut headers = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        match FieldKind::parse(&field.attrs)? {
+            Some(FieldKind::Query) => set_once(&mut query, field, member, "query")?,
+            Some(FieldKind::Body) => set_once(&mut body, field, member, "body")?,
+            Some(FieldKind::Header(name)) => headers.push(header(field, &member, &name)),
+            None => {}
+        }
+    }
+
+    let format = attrs
+        .body
+        .map(|(format, _)| format)
+        .unwrap_or(BodyFormat::Json);
+    let format = match format {
+        BodyFormat::Json => quote!(Json),
+        BodyFormat::Form => quote!(Form),
+    };
+    let query = match (query, attrs.query) {
+        (Some(_), Some(span)) => {
+            return Err(syn::Error::new(
+                span,
+                "query is set on both the struct and a field",
+            ))
+        }
+        (Some((ty, member)), None) => Some((quote!(#ty), quote!(&self.#member))),
+        (None, Some(_)) => Some((quote!(Self), quote!(self))),
+        (None, None) => None,
+    };
+    let body = match (body, attrs.body) {
+        (Some((ty, member)), _) => Some((quote!(#ty), quote!(&self.#member))),
+        (None, Some(_)) => Some((quote!(Self), quote!(self))),
+        (None, None) => None,
+    };
+    // Query parameters are sent as the data of the request, unless there is also a body
+    let (data, query) = match (query, body) {
+        (Some((_, query)), Some((ty, value))) => (Some((ty, quote!(#format(#value)))), Some(query)),
+        (Some((ty, value)), None) => (Some((ty, quote!(Query(#value)))), None),
+        (None, Some((ty, value))) => (Some((ty, quote!(#format(#value)))), None),
+        (None, None) => (None, None),
+    };
+    let query = query.map(|query| {
+        quote! {
+            fn query(&self) -> ::std::option::Option<::tower_api_client::Query<'_>> {
+                ::std::option::Option::Some(::tower_api_client::Query::new(#query))
+            }
+        }
+    });
+    let data = match data {
+        Some((ty, value)) => quote! {
+            type Data = #ty;
+
+            fn data(&self) -> ::tower_api_client::RequestData<&Self::Data> {
+                ::tower_api_client::RequestData::#value
+            }
+        },
+        None => quote! {
+            type Data = ();
+        },
+    };
+
+    let endpoint = endpoint(path, fields)?;
+    let method = attrs.method.map(|method| {
+        quote!(const METHOD: ::tower_api_client::Method = ::tower_api_client::Method::#method;)
+    });
+    let response = attrs
+        .response
+        .map(|ty| quote!(#ty))
+        .unwrap_or_else(|| quote!(::tower_api_client::EmptyResponse));
+    let headers = (!headers.is_empty()).then(|| {
+        quote! {
+            fn try_headers(
+                &self,
+            ) -> ::std::result::Result<::tower_api_client::header::HeaderMap, ::tower_api_client::Error>
+            {
+                let mut headers = ::tower_api_client::header::HeaderMap::new();
+                #(#headers)*
+                ::std::result::Result::Ok(headers)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let error = attrs.error.map(|ty| {
+        quote! {
+            impl #impl_generics ::tower_api_client::TypedErrorRequest for #name #ty_generics #where_clause {
+                type ErrorBody = #ty;
+            }
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::tower_api_client::Request for #name #ty_generics #where_clause {
+            type Response = #response;
+            #data
+
+            #method
+
+            fn endpoint(&self) -> ::tower_api_client::Path<'_> {
+                #endpoint
+            }
+
+            #query
+
+            #headers
+        }
+
+        #error
+    })
+}
+
+fn set_once(
+    slot: &mut Option<(Type, Member)>,
+    field: &Field,
+    member: Member,
+    kind: &str,
+) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            field.span(),
+            format!("only one field can be marked as {}", kind),
+        ));
+    }
+    *slot = Some((field.ty.clone(), member));
+    Ok(())
+}
+
+fn header(field: &Field, member: &Member, name: &LitStr) -> TokenStream2 {
+    let name = name.value().to_ascii_lowercase();
+    let insert = |value: TokenStream2| {
+        quote! {
+            let value = <::tower_api_client::header::HeaderValue as ::std::convert::TryFrom<_>>::try_from(
+                ::std::string::ToString::to_string(#value),
+            )?;
+            headers.insert(::tower_api_client::header::HeaderName::from_static(#name), value);
+        }
+    };
+    if is_option(&field.ty) {
+        let insert = insert(quote!(value));
+        quote! {
+            if let ::std::option::Option::Some(value) = &self.#member {
+                #insert
+            }
+        }
+    } else {
+        insert(quote!(&self.#member))
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Build the endpoint from the path template, interpolating the fields named in braces as
+/// percent-encoded path segments.
+fn endpoint(path: &LitStr, fields: &Fields) -> syn::Result<TokenStream2> {
+    let template = path.value();
+    let mut params = Vec::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err(syn::Error::new(path.span(), "unmatched `}` in path"));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| syn::Error::new(path.span(), "unmatched `{` in path"))?;
+        let name = &rest[start + 1..start + end];
+        let field = fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .find(|ident| *ident == name)
+            .ok_or_else(|| syn::Error::new(path.span(), format!("no field named `{}`", name)))?;
+        params.push(quote!(.param(#name, &self.#field)));
+        rest = &rest[start + end + 1..];
+    }
+
+    if params.is_empty() {
+        Ok(quote!(::tower_api_client::Path::from(#path)))
+    } else {
+        Ok(quote!(::tower_api_client::Path::new(#path) #(#params)*))
+    }
+}
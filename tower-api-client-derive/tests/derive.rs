@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower_api_client::{Client, Error, Method, Request, RequestData, TypedError};
+use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Request)]
+#[request(path = "/users/{id}", response = User)]
+struct GetUser {
+    id: u64,
+    #[request(header = "X-Request-Id")]
+    request_id: String,
+    #[request(header = "X-Trace")]
+    trace: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NewUser {
+    name: String,
+}
+
+#[derive(Request)]
+#[request(method = "POST", path = "/orgs/{org}/users", response = User)]
+struct CreateUser {
+    org: String,
+    #[request(body)]
+    user: NewUser,
+}
+
+#[derive(Serialize)]
+struct Filter {
+    name: String,
+}
+
+#[derive(Request)]
+#[request(path = "/users", response = Vec<User>)]
+struct FilterUsers {
+    #[request(query)]
+    filter: Filter,
+}
+
+#[derive(Serialize)]
+struct Options {
+    notify: bool,
+}
+
+#[derive(Request)]
+#[request(method = "PUT", path = "/users/{id}", body = json, response = User)]
+struct UpdateUser {
+    id: u64,
+    #[request(query)]
+    options: Options,
+    #[request(body)]
+    user: NewUser,
+}
+
+#[derive(Serialize, Request)]
+#[request(method = "post", path = "/users/{id}/rename", body = form)]
+struct RenameUser {
+    #[serde(skip)]
+    id: u64,
+    name: String,
+}
+
+#[derive(Serialize, Request)]
+#[request(path = "/search", query, response = Vec<User>)]
+struct Search<'a> {
+    q: &'a str,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Request)]
+#[request(method = "DELETE", path = "/users/{id}", error = ApiError)]
+struct DeleteUser {
+    id: u64,
+}
+
+#[test]
+fn generated_impl() {
+    assert_eq!(GetUser::METHOD, Method::GET);
+    assert_eq!(CreateUser::METHOD, Method::POST);
+    assert_eq!(RenameUser::METHOD, Method::POST);
+
+    let request = GetUser {
+        id: 1,
+        request_id: "abc".into(),
+        trace: None,
+    };
+    assert_eq!(request.endpoint().to_string(), "/users/1");
+    let headers = request.try_headers().unwrap();
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers["x-request-id"], "abc");
+    assert!(matches!(request.data(), RequestData::Empty));
+
+    let request = CreateUser {
+        org: "a/b c".into(),
+        user: NewUser {
+            name: "Alice".into(),
+        },
+    };
+    assert_eq!(request.endpoint().to_string(), "/orgs/a%2Fb%20c/users");
+
+    let request = Search { q: "bob" };
+    assert_eq!(request.endpoint().to_string(), "/search");
+    assert!(matches!(request.data(), RequestData::Query(_)));
+}
+
+#[tokio::test]
+async fn path_and_headers() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .and(path("/users/7"))
+        .and(header("X-Request-Id", "abc"))
+        .and(header("X-Trace", "xyz"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 7, "name": "Bob" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = client
+        .send(GetUser {
+            id: 7,
+            request_id: "abc".into(),
+            trace: Some("xyz".into()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: 7,
+            name: "Bob".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn json_body_field() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("POST"))
+        .and(path("/orgs/acme/users"))
+        .and(body_json(json!({ "name": "Alice" })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": 1, "name": "Alice" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = client
+        .send(CreateUser {
+            org: "acme".into(),
+            user: NewUser {
+                name: "Alice".into(),
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(user.id, 1);
+}
+
+#[tokio::test]
+async fn query_field() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .and(path("/users"))
+        .and(query_param("name", "Alice"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let users = client
+        .send(FilterUsers {
+            filter: Filter {
+                name: "Alice".into(),
+            },
+        })
+        .await
+        .unwrap();
+    assert!(users.is_empty());
+}
+
+#[tokio::test]
+async fn struct_as_form_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("POST"))
+        .and(path("/users/3/rename"))
+        .and(body_string("name=Carol"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .send(RenameUser {
+            id: 3,
+            name: "Carol".into(),
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn query_and_body_fields() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("PUT"))
+        .and(path("/users/5"))
+        .and(query_param("notify", "true"))
+        .and(body_json(json!({ "name": "Dave" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 5, "name": "Dave" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = client
+        .send(UpdateUser {
+            id: 5,
+            options: Options { notify: true },
+            user: NewUser {
+                name: "Dave".into(),
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(user.name, "Dave");
+}
+
+#[tokio::test]
+async fn invalid_header_value() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 7, "name": "Bob" })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let error = client
+        .send(GetUser {
+            id: 7,
+            request_id: "a\nb".into(),
+            trace: None,
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Http(_)));
+}
+
+#[tokio::test]
+async fn typed_error() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    Mock::given(method("DELETE"))
+        .and(path("/users/7"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({ "message": "Forbidden" })))
+        .mount(&server)
+        .await;
+
+    let error = client.send_typed(DeleteUser { id: 7 }).await.unwrap_err();
+    assert!(matches!(
+        error,
+        TypedError::Body(ApiError { message }, Error::ClientError(_)) if message == "Forbidden"
+    ));
+}